@@ -0,0 +1,369 @@
+use std::collections::HashSet;
+
+use bevy::{prelude::*, sprite::Anchor};
+use itertools::Itertools;
+
+use crate::{
+    component::{
+        pla2::{hex_to_color, PlaComponent},
+        skin::{AreaStyle, LineStyle, PointStyle, Skin, SkinComponent},
+    },
+    misc_config::settings::MiscSettings,
+    state::IntoSystemConfigExt,
+    ui::map::zoom::{SkinZoom, Zoom},
+};
+
+#[derive(Resource, Default, Clone)]
+pub struct LabelFont(pub Handle<Font>);
+
+#[derive(Component)]
+pub struct ComponentLabel(pub Entity);
+
+/// A label whose layout depends on the camera scale, so it is rebuilt when zooming
+#[derive(Component)]
+pub struct FitsToScale;
+
+/// Where a label sits: `pos` in the world, moved by `offset` screen pixels
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct LabelPosition {
+    pub pos: Vec2,
+    pub offset: Vec2,
+}
+
+impl LabelPosition {
+    #[must_use]
+    pub fn translation(self, scale: f32) -> Vec3 {
+        (self.pos + self.offset * scale).extend(95.0)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LabelPlacement {
+    pub pos: LabelPosition,
+    pub rotation: f32,
+    pub size: f32,
+    pub colour: Color,
+    pub anchor: Anchor,
+    pub text: String,
+    pub fits_to_scale: bool,
+}
+
+/// Parses a PIL-style anchor, e.g. `lt` or `mm`
+fn parse_anchor(anchor: &str) -> Anchor {
+    let mut chars = anchor.chars();
+    let x = match chars.next() {
+        Some('l') => -0.5,
+        Some('r') => 0.5,
+        _ => 0.0,
+    };
+    let y = match chars.next() {
+        Some('a' | 't') => 0.5,
+        Some('b' | 'd' | 's') => -0.5,
+        _ => 0.0,
+    };
+    Anchor::Custom(Vec2::new(x, y))
+}
+
+/// Rough width of a label in pixels, used to skip labels that do not fit their component
+fn text_width(text: &str, size: f32) -> f32 {
+    text.chars().count() as f32 * size * 0.6
+}
+
+/// The point `distance` along a path, and the direction of the path there
+fn point_along(nodes: &[Vec2], mut distance: f32) -> Option<(Vec2, Vec2)> {
+    let mut end = None;
+    for (a, b) in nodes.iter().tuple_windows() {
+        let length = a.distance(*b);
+        if length <= f32::EPSILON {
+            continue;
+        }
+        let direction = (*b - *a) / length;
+        if distance <= length {
+            return Some((*a + direction * distance, direction));
+        }
+        distance -= length;
+        end = Some((*b, direction));
+    }
+    end
+}
+
+/// Lays out each character of a label along the middle of a path, rotated with it and kept upright.
+/// Nothing is placed if the label, `scale` times its width in pixels, is longer than the path
+fn along_path(
+    nodes: &[Vec2],
+    closed: bool,
+    text: &str,
+    size: f32,
+    offset: f32,
+    scale: f32,
+) -> Vec<(LabelPosition, f32, char)> {
+    let mut nodes = nodes.to_vec();
+    let closing = nodes.first().copied().filter(|_| closed);
+    nodes.extend(closing);
+    let length = nodes
+        .iter()
+        .tuple_windows()
+        .map(|(a, b)| a.distance(*b))
+        .sum::<f32>();
+    let width = text_width(text, size) * scale;
+    if text.is_empty() || length < width {
+        return Vec::new();
+    }
+    let start = (length - width) / 2.0;
+    let (Some((first, _)), Some((last, _))) = (
+        point_along(&nodes, start),
+        point_along(&nodes, start + width),
+    ) else {
+        return Vec::new();
+    };
+    if last.x < first.x {
+        nodes.reverse();
+    }
+    let glyph_width = width / text.chars().count() as f32;
+    text.chars()
+        .enumerate()
+        .filter(|(_, c)| !c.is_whitespace())
+        .filter_map(|(i, c)| {
+            let (pos, direction) = point_along(
+                &nodes,
+                (i as f32).mul_add(glyph_width, start + glyph_width / 2.0),
+            )?;
+            Some((
+                LabelPosition {
+                    pos,
+                    offset: -direction.perp() * offset,
+                },
+                direction.to_angle(),
+                c,
+            ))
+        })
+        .collect()
+}
+
+impl PlaComponent {
+    #[must_use]
+    pub fn label_placements(&self, skin: &Skin, scale: f32, zoom: SkinZoom) -> Vec<LabelPlacement> {
+        let Some(skin_type) = skin.get_type(&self.ty) else {
+            return Vec::new();
+        };
+        let nodes = self.nodes.iter().map(|a| a.0.as_vec2()).collect::<Vec<_>>();
+        let Some(first) = nodes.first().copied() else {
+            return Vec::new();
+        };
+        let width = |size: f32| text_width(&self.display_name, size) * scale;
+
+        let mut placements = Vec::new();
+        match skin_type {
+            SkinComponent::Point { styles, .. } => {
+                let Some((band_min, styles)) = SkinComponent::style_at_zoom(styles, zoom) else {
+                    return placements;
+                };
+                for style in styles {
+                    if let PointStyle::Text {
+                        zoom_multiplier,
+                        colour: Some(colour),
+                        size,
+                        offset,
+                        anchor,
+                    } = style
+                    {
+                        let size =
+                            size * SkinComponent::zoom_scale(*zoom_multiplier, band_min, zoom);
+                        placements.push(LabelPlacement {
+                            pos: LabelPosition {
+                                pos: first,
+                                offset: Vec2::new(offset.x, -offset.y),
+                            },
+                            rotation: 0.0,
+                            size,
+                            colour: hex_to_color(*colour),
+                            anchor: parse_anchor(anchor),
+                            text: self.display_name.clone(),
+                            fits_to_scale: false,
+                        });
+                    }
+                }
+            }
+            SkinComponent::Line { styles, .. } => {
+                let Some((band_min, styles)) = SkinComponent::style_at_zoom(styles, zoom) else {
+                    return placements;
+                };
+                for style in styles {
+                    if let LineStyle::Text {
+                        zoom_multiplier,
+                        colour: Some(colour),
+                        size,
+                        offset,
+                        ..
+                    } = style
+                    {
+                        let size =
+                            size * SkinComponent::zoom_scale(*zoom_multiplier, band_min, zoom);
+                        for (pos, rotation, glyph) in
+                            along_path(&nodes, false, &self.display_name, size, *offset, scale)
+                        {
+                            placements.push(LabelPlacement {
+                                pos,
+                                rotation,
+                                size,
+                                colour: hex_to_color(*colour),
+                                anchor: Anchor::Center,
+                                text: glyph.to_string(),
+                                fits_to_scale: true,
+                            });
+                        }
+                    }
+                }
+            }
+            SkinComponent::Area { styles, .. } => {
+                let (min, max) = nodes.iter().fold((first, first), |(min, max), node| {
+                    (min.min(*node), max.max(*node))
+                });
+                let Some((band_min, styles)) = SkinComponent::style_at_zoom(styles, zoom) else {
+                    return placements;
+                };
+                for style in styles {
+                    match style {
+                        AreaStyle::CenterText {
+                            zoom_multiplier,
+                            colour: Some(colour),
+                            offset,
+                            size,
+                        } => {
+                            let size =
+                                size * SkinComponent::zoom_scale(*zoom_multiplier, band_min, zoom);
+                            if max.x - min.x < width(size) {
+                                continue;
+                            }
+                            placements.push(LabelPlacement {
+                                pos: LabelPosition {
+                                    pos: nodes.iter().sum::<Vec2>() / nodes.len() as f32,
+                                    offset: Vec2::new(offset.x, -offset.y),
+                                },
+                                rotation: 0.0,
+                                size,
+                                colour: hex_to_color(*colour),
+                                anchor: Anchor::Center,
+                                text: self.display_name.clone(),
+                                fits_to_scale: true,
+                            });
+                        }
+                        AreaStyle::BorderText {
+                            zoom_multiplier,
+                            colour: Some(colour),
+                            offset,
+                            size,
+                        } => {
+                            let size =
+                                size * SkinComponent::zoom_scale(*zoom_multiplier, band_min, zoom);
+                            for (pos, rotation, glyph) in
+                                along_path(&nodes, true, &self.display_name, size, *offset, scale)
+                            {
+                                placements.push(LabelPlacement {
+                                    pos,
+                                    rotation,
+                                    size,
+                                    colour: hex_to_color(*colour),
+                                    anchor: Anchor::Center,
+                                    text: glyph.to_string(),
+                                    fits_to_scale: true,
+                                });
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        if let Some(prune) = skin.prune_small_text {
+            placements.retain(|a| a.size >= prune);
+        }
+        placements
+    }
+}
+
+#[tracing::instrument(skip_all)]
+pub fn update_labels_sy(
+    mut commands: Commands,
+    components: Query<(Entity, Ref<PlaComponent>)>,
+    mut labels: Query<(
+        Entity,
+        &ComponentLabel,
+        &LabelPosition,
+        &mut Transform,
+        Has<FitsToScale>,
+    )>,
+    mut removed: RemovedComponents<PlaComponent>,
+    projection: Query<&Projection, With<Camera>>,
+    skin: Res<Skin>,
+    misc_settings: Res<MiscSettings>,
+    zoom: Res<Zoom>,
+    skin_zoom: Res<SkinZoom>,
+    font: Res<LabelFont>,
+) {
+    let Ok(Projection::Orthographic(projection)) = projection.single() else {
+        return;
+    };
+    let update_all = skin_zoom.is_changed() || misc_settings.is_changed() || skin.is_changed();
+    let removed = removed.read().collect::<HashSet<_>>();
+    let mut to_update = components
+        .iter()
+        .filter(|(_, pla)| update_all || pla.is_changed())
+        .map(|(e, _)| e)
+        .collect::<HashSet<_>>();
+    if zoom.is_changed() && !update_all {
+        for (_, ComponentLabel(of), pos, mut transform, fits_to_scale) in &mut labels {
+            if fits_to_scale {
+                to_update.insert(*of);
+                continue;
+            }
+            transform.translation = pos.translation(projection.scale);
+            transform.scale = Vec3::splat(projection.scale);
+        }
+    }
+    for (label, ComponentLabel(of), _, _, _) in &labels {
+        if removed.contains(of) || to_update.contains(of) {
+            commands.entity(label).despawn();
+        }
+    }
+    if !misc_settings.show_labels || to_update.is_empty() {
+        return;
+    }
+
+    for e in to_update {
+        let Ok((_, pla)) = components.get(e) else {
+            continue;
+        };
+        if pla.display_name.is_empty() {
+            continue;
+        }
+        for placement in pla.label_placements(&skin, projection.scale, *skin_zoom) {
+            let mut label = commands.spawn((
+                Text2d::new(placement.text),
+                TextFont {
+                    font: font.0.clone(),
+                    font_size: placement.size,
+                    ..default()
+                },
+                TextColor(placement.colour),
+                placement.anchor,
+                Transform::from_translation(placement.pos.translation(projection.scale))
+                    .with_rotation(Quat::from_rotation_z(placement.rotation))
+                    .with_scale(Vec3::splat(projection.scale)),
+                ComponentLabel(e),
+                placement.pos,
+            ));
+            if placement.fits_to_scale {
+                label.insert(FitsToScale);
+            }
+        }
+    }
+}
+
+pub struct LabelPlugin;
+impl Plugin for LabelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LabelFont>()
+            .add_systems(Update, update_labels_sy.run_if_not_loading());
+    }
+}
@@ -1,9 +1,11 @@
 use bevy::{app::PluginGroupBuilder, prelude::*};
 
 pub mod hovering;
+pub mod labels;
 pub mod moving;
 pub mod rendering;
 pub mod selecting;
+pub mod transforming;
 
 pub struct ComponentActionPlugins;
 
@@ -13,6 +15,8 @@ impl PluginGroup for ComponentActionPlugins {
             .add(moving::MoveComponentPlugin)
             .add(selecting::SelectComponentPlugin)
             .add(hovering::HoverComponentPlugin)
+            .add(labels::LabelPlugin)
             .add(rendering::RenderComponentPlugin)
+            .add(transforming::TransformComponentPlugin)
     }
 }
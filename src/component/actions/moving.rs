@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use bevy_egui::EguiContexts;
 
 use crate::{
     component::{
@@ -7,7 +8,8 @@ use crate::{
         skin::Skin,
     },
     history::{HistoryEntry, HistoryEv},
-    state::EditorState,
+    keymaps::settings::{KeymapAction, KeymapSettings},
+    state::{EditorState, IntoSystemConfigExt},
     ui::{
         cursor::mouse_pos::MousePosWorld, map::window::PointerWithinTilemap, panel::status::Status,
     },
@@ -40,7 +42,7 @@ pub fn on_right_click_drag(
     transform.translation.x =
         (move_data.old_translation.x + mouse_pos_world.x - move_data.old_mouse_pos_world.x).round();
     transform.translation.y =
-        (move_data.old_translation.x + mouse_pos_world.y - move_data.old_mouse_pos_world.y).round();
+        (move_data.old_translation.y + mouse_pos_world.y - move_data.old_mouse_pos_world.y).round();
 }
 
 #[tracing::instrument(skip_all)]
@@ -103,11 +105,11 @@ pub fn on_right_click_drag_end(
             .round()
             .as_ivec2();
     }
-    commands.trigger(HistoryEv::one_history(HistoryEntry::Component {
+    commands.trigger(HistoryEv::Coalesce(vec![HistoryEntry::Component {
         e,
         before: Some(old_pla.into()),
         after: Some(pla.to_owned().into()),
-    }));
+    }]));
     commands
         .entity(e)
         .remove::<MoveData>()
@@ -116,11 +118,71 @@ pub fn on_right_click_drag_end(
     info!("Ended move");
 }
 
+#[tracing::instrument(skip_all)]
+pub fn nudge_sy(
+    mut commands: Commands,
+    mut selected: Query<(Entity, &mut PlaComponent), With<SelectedComponent>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    keymap: Res<KeymapSettings>,
+    mut status: ResMut<Status>,
+    mut ctx: EguiContexts,
+) {
+    if ctx
+        .try_ctx_mut()
+        .is_some_and(|a| a.memory(|a| a.focused().is_some()))
+    {
+        return;
+    }
+    let key = |action| keymap.0.get(&action);
+    let direction = [
+        (KeymapAction::NudgeUp, IVec2::Y),
+        (KeymapAction::NudgeDown, IVec2::NEG_Y),
+        (KeymapAction::NudgeLeft, IVec2::NEG_X),
+        (KeymapAction::NudgeRight, IVec2::X),
+    ]
+    .into_iter()
+    .filter(|(action, _)| key(*action).is_some_and(|a| keys.just_pressed(*a)))
+    .map(|(_, direction)| direction)
+    .sum::<IVec2>();
+    if direction == IVec2::ZERO || selected.is_empty() {
+        return;
+    }
+    let offset = if key(KeymapAction::NudgeLarge).is_some_and(|a| keys.pressed(*a)) {
+        direction * 10
+    } else {
+        direction
+    };
+
+    let histories = selected
+        .iter_mut()
+        .map(|(e, mut pla)| {
+            let old_pla = pla.to_owned();
+            for node in &mut pla.nodes {
+                node.0 += offset;
+            }
+            commands.entity(e).trigger(RenderEv::default());
+            HistoryEntry::Component {
+                e,
+                before: Some(old_pla.into()),
+                after: Some(pla.to_owned().into()),
+            }
+        })
+        .collect::<Vec<_>>();
+    status.set(format!(
+        "Nudged {} components by {}, {}",
+        histories.len(),
+        offset.x,
+        -offset.y
+    ));
+    commands.trigger(HistoryEv::Coalesce(histories));
+}
+
 pub struct MoveComponentPlugin;
 impl Plugin for MoveComponentPlugin {
     fn build(&self, app: &mut App) {
         app.add_observer(on_right_click_drag)
             .add_observer(on_right_click_drag_start)
-            .add_observer(on_right_click_drag_end);
+            .add_observer(on_right_click_drag_end)
+            .add_systems(Update, nudge_sy.run_if_not_loading());
     }
 }
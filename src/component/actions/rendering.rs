@@ -3,26 +3,38 @@ use bevy::{
     prelude::*,
     render::primitives::Aabb,
 };
+use bevy_prototype_lyon::prelude::*;
 use itertools::Itertools;
 
 use crate::{
     component::{
         actions::{hovering::HoveredComponent, selecting::SelectedComponent},
         circle::make_circle,
-        pla2::{ComponentType, HighlightExt, PlaComponent},
-        skin::Skin,
+        pla2::{line_path, ComponentType, HighlightExt, PlaComponent},
+        skin::{Skin, SkinImages},
         tools::creating::CreatedComponent,
     },
     misc_config::settings::MiscSettings,
+    project::Namespaces,
     state::EditorState,
-    ui::{cursor::mouse_pos::MousePosWorld, map::zoom::Zoom},
+    ui::{
+        cursor::mouse_pos::MousePosWorld,
+        map::{
+            settings::TileSettings,
+            zoom::{SkinZoom, Zoom},
+        },
+    },
 };
 
+/// How opaque components in locked namespaces are drawn
+const LOCKED_ALPHA: f32 = 0.5;
+
 #[tracing::instrument(skip_all)]
 pub fn on_render(
     trigger: Trigger<RenderEv>,
     mut commands: Commands,
     skin: Res<Skin>,
+    skin_images: Res<SkinImages>,
     query: Query<(
         &mut PlaComponent,
         Option<&HoveredComponent>,
@@ -30,9 +42,11 @@ pub fn on_render(
         Option<&CreatedComponent>,
     )>,
     zoom: Res<Zoom>,
+    skin_zoom: Res<SkinZoom>,
     misc_settings: Res<MiscSettings>,
     state: Res<State<EditorState>>,
     mouse_pos_world: Res<MousePosWorld>,
+    namespaces: Res<Namespaces>,
 ) {
     let e = trigger.target();
     let Ok((pla, hovered, selected, created)) = query.get(e) else {
@@ -41,18 +55,99 @@ pub fn on_render(
     let pla = trigger.0.as_ref().unwrap_or(pla);
     let ty = pla.get_skin_type(&skin);
 
-    let (mut shape, _) = pla.get_shape(&skin);
-    let (mut fill, mut stroke) = (pla.get_fill(&skin), pla.get_stroke(&skin));
+    let icon = pla.icon(&skin, &skin_images, *skin_zoom);
+    let icon_point = ty == ComponentType::Point && icon.is_some();
+
+    let (mut fill, mut stroke) = (
+        pla.get_fill(&skin, *skin_zoom),
+        pla.get_stroke(&skin, *skin_zoom),
+    );
+    let mut shape = match &icon {
+        Some(icon) if icon_point => {
+            fill = Fill::color(Color::NONE);
+            ShapeBuilder::with(&shapes::Rectangle {
+                extents: icon.size,
+                origin: RectangleOrigin::CustomCenter(icon.offset),
+                ..default()
+            })
+            .fill(fill)
+            .build()
+        }
+        _ => pla.get_shape(&skin, *skin_zoom).0,
+    };
     if selected.is_some() {
-        shape.fill = (fill.color != Color::NONE).then(|| fill.select(ty).to_owned());
+        shape.fill = (fill.color != Color::NONE || icon_point).then(|| fill.select(ty).to_owned());
         shape.stroke = (stroke.color != Color::NONE).then(|| stroke.select(ty).to_owned());
     } else if hovered.is_some() && created.is_none() {
-        shape.fill = (fill.color != Color::NONE).then(|| fill.hover(ty).to_owned());
+        shape.fill = (fill.color != Color::NONE || icon_point).then(|| fill.hover(ty).to_owned());
         shape.stroke = (stroke.color != Color::NONE).then(|| stroke.hover(ty).to_owned());
     }
+    let locked = namespaces.locked.contains(&pla.namespace);
+    let dim = |colour: Color| {
+        if locked {
+            colour.with_alpha(colour.alpha() * LOCKED_ALPHA)
+        } else {
+            colour
+        }
+    };
+    if let Some(fill) = &mut shape.fill {
+        fill.color = dim(fill.color);
+    }
+    if let Some(stroke) = &mut shape.stroke {
+        stroke.color = dim(stroke.color);
+    }
     commands.entity(e).remove::<Aabb>().insert(shape);
 
     commands.entity(e).despawn_related::<Children>();
+    if let Some(icon) = icon {
+        let pos = if ty == ComponentType::Point {
+            icon.offset
+        } else {
+            pla.nodes.iter().map(|a| a.0.as_vec2()).sum::<Vec2>() / pla.nodes.len().max(1) as f32
+                + icon.offset
+        };
+        let sprite = commands
+            .spawn((
+                Sprite {
+                    image: icon.image,
+                    custom_size: Some(icon.size),
+                    color: dim(Color::WHITE),
+                    ..default()
+                },
+                Transform::from_translation(pos.extend(0.01)),
+            ))
+            .id();
+        commands.entity(e).add_child(sprite);
+    }
+    if ty == ComponentType::Line {
+        let nodes = pla.nodes.iter().map(|a| a.0.as_vec2()).collect::<Vec<_>>();
+        let layers = pla
+            .line_layers(&skin, *skin_zoom)
+            .into_iter()
+            .skip(1)
+            .enumerate()
+            .map(|(i, layer)| {
+                let mut stroke = layer.stroke;
+                if stroke.color != Color::NONE {
+                    if selected.is_some() {
+                        stroke.select(ty);
+                    } else if hovered.is_some() && created.is_none() {
+                        stroke.hover(ty);
+                    }
+                }
+                stroke.color = dim(stroke.color);
+                commands
+                    .spawn((
+                        ShapeBuilder::with(&line_path(&nodes, layer.dash.as_deref()))
+                            .stroke(stroke)
+                            .build(),
+                        Transform::from_xyz(0.0, 0.0, (i + 1) as f32 * 0.0001),
+                    ))
+                    .id()
+            })
+            .collect::<Vec<_>>();
+        commands.entity(e).add_children(&layers);
+    }
     if *state == EditorState::EditingNodes && selected.is_some() {
         let filter_by_distance = |coord: &IVec2| -> bool {
             if pla.nodes.len() > misc_settings.hide_far_handles_threshold {
@@ -143,17 +238,41 @@ pub fn rerender_selected_sy(
     }
 }
 
+#[tracing::instrument(skip_all)]
+pub fn update_skin_zoom_sy(
+    mut skin_zoom: ResMut<SkinZoom>,
+    zoom: Res<Zoom>,
+    tile_settings: Res<TileSettings>,
+) {
+    skin_zoom.set_if_neq(zoom.skin_zoom(tile_settings.basemap()));
+}
+
+pub fn rerender_all_sy(components: Query<Entity, With<PlaComponent>>, mut commands: Commands) {
+    for e in &components {
+        commands.trigger_targets(RenderEv::default(), e);
+    }
+}
+
 pub struct RenderComponentPlugin;
 impl Plugin for RenderComponentPlugin {
     fn build(&self, app: &mut App) {
-        app.add_observer(on_render).add_systems(
-            Update,
-            rerender_selected_sy.run_if(
-                resource_changed::<Zoom>
-                    .or(in_state(EditorState::EditingNodes).and(resource_changed::<MousePosWorld>))
-                    .or(state_changed::<EditorState>),
-            ),
-        );
+        app.init_resource::<SkinZoom>()
+            .add_observer(on_render)
+            .add_systems(
+                Update,
+                (
+                    update_skin_zoom_sy.run_if(resource_changed::<Zoom>),
+                    rerender_all_sy
+                        .run_if(resource_changed::<SkinZoom>.or(resource_changed::<SkinImages>)),
+                    rerender_selected_sy.run_if(
+                        resource_changed::<Zoom>
+                            .or(in_state(EditorState::EditingNodes)
+                                .and(resource_changed::<MousePosWorld>))
+                            .or(state_changed::<EditorState>),
+                    ),
+                )
+                    .chain(),
+            );
     }
 }
 
@@ -2,6 +2,7 @@ use bevy::prelude::*;
 
 use crate::{
     component::{actions::rendering::RenderEv, pla2::PlaComponent},
+    project::Namespaces,
     state::EditorState,
     ui::{cursor::mouse_events::Click2, map::window::PointerWithinTilemap, panel::status::Status},
 };
@@ -12,12 +13,15 @@ pub fn on_select_left_click(
     mut commands: Commands,
     state: Res<State<EditorState>>,
     components: Query<(), With<PlaComponent>>,
+    selected: Query<(), With<SelectedComponent>>,
+    keys: Res<ButtonInput<KeyCode>>,
     mut status: ResMut<Status>,
     pointer_within_tilemap: Option<Res<PointerWithinTilemap>>,
 ) {
     if pointer_within_tilemap.is_none()
         || state.component_type().is_some()
         || *state == EditorState::DeletingComponent
+        || *state == EditorState::Measuring
         || trigger.button != PointerButton::Primary
     {
         return;
@@ -28,6 +32,15 @@ pub fn on_select_left_click(
         info!("Selected nothing, deselecting");
         commands.trigger(SelectEv::DeselectAll);
         status.set("Deselected component");
+    } else if components.contains(e) && keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+    {
+        if selected.contains(e) {
+            commands.trigger_targets(SelectEv::Deselect, e);
+            status.set("Removed component from selection");
+        } else {
+            commands.trigger_targets(SelectEv::Select, e);
+            status.set("Added component to selection");
+        }
     } else if components.contains(e) {
         commands.trigger_targets(SelectEv::SelectOne, e);
         status.set("Selected component");
@@ -39,6 +52,8 @@ pub fn on_select(
     trigger: Trigger<SelectEv>,
     mut commands: Commands,
     mut query: ParamSet<(Query<&PlaComponent>, Query<Entity, With<SelectedComponent>>)>,
+    namespaces: Res<Namespaces>,
+    mut status: ResMut<Status>,
 ) {
     let e = trigger.target();
     if e == Entity::PLACEHOLDER && *trigger.event() != SelectEv::DeselectAll {
@@ -46,6 +61,12 @@ pub fn on_select(
     }
     match trigger.event() {
         SelectEv::Select => {
+            if let Ok(pla) = query.p0().get(e) {
+                if namespaces.locked.contains(&pla.namespace) {
+                    status.set(format!("Namespace {} is locked", pla.namespace));
+                    return;
+                }
+            }
             info!(?e, "Selecting entity");
             commands
                 .entity(e)
@@ -0,0 +1,417 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+};
+
+use bevy::{
+    color::palettes::basic::{BLACK, FUCHSIA},
+    prelude::*,
+    render::primitives::Aabb,
+};
+
+use crate::{
+    component::{
+        actions::{rendering::RenderEv, selecting::SelectedComponent},
+        circle::make_circle,
+        pla2::PlaComponent,
+    },
+    history::{HistoryEntry, HistoryEv},
+    misc_config::settings::MiscSettings,
+    state::EditorState,
+    ui::{
+        cursor::{mouse_events::Click2, mouse_pos::MousePosWorld},
+        map::{window::PointerWithinTilemap, zoom::Zoom},
+        panel::status::Status,
+    },
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Pivot {
+    #[default]
+    Centroid,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Cursor,
+}
+impl Display for Pivot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Centroid => "Centroid",
+                Self::TopLeft => "Top left",
+                Self::TopRight => "Top right",
+                Self::BottomLeft => "Bottom left",
+                Self::BottomRight => "Bottom right",
+                Self::Cursor => "Cursor",
+            }
+        )
+    }
+}
+impl Pivot {
+    pub const ALL: [Self; 6] = [
+        Self::Centroid,
+        Self::TopLeft,
+        Self::TopRight,
+        Self::BottomLeft,
+        Self::BottomRight,
+        Self::Cursor,
+    ];
+
+    #[must_use]
+    pub fn resolve(self, nodes: &[Vec2], cursor: Vec2) -> Vec2 {
+        let (min, max) = bounding_box(nodes);
+        match self {
+            Self::Centroid => nodes.iter().sum::<Vec2>() / nodes.len().max(1) as f32,
+            Self::TopLeft => Vec2::new(min.x, max.y),
+            Self::TopRight => max,
+            Self::BottomLeft => min,
+            Self::BottomRight => Vec2::new(max.x, min.y),
+            Self::Cursor => cursor,
+        }
+    }
+}
+
+#[must_use]
+pub fn bounding_box(nodes: &[Vec2]) -> (Vec2, Vec2) {
+    if nodes.is_empty() {
+        return (Vec2::ZERO, Vec2::ZERO);
+    }
+    nodes.iter().fold(
+        (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+        |(min, max), node| (min.min(*node), max.max(*node)),
+    )
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct TransformSettings {
+    pub pivot: Pivot,
+    pub cursor_pivot: Vec2,
+    pub snap_angle: f32,
+    pub uniform: bool,
+    pub angle: f32,
+    pub scale: Vec2,
+}
+
+impl Default for TransformSettings {
+    fn default() -> Self {
+        Self {
+            pivot: Pivot::default(),
+            cursor_pivot: Vec2::ZERO,
+            snap_angle: 15.0,
+            uniform: true,
+            angle: 0.0,
+            scale: Vec2::ONE,
+        }
+    }
+}
+
+impl TransformSettings {
+    #[must_use]
+    pub fn snap(&self, angle: f32) -> f32 {
+        if self.snap_angle > 0.0 {
+            (angle / self.snap_angle).round() * self.snap_angle
+        } else {
+            angle
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Event)]
+pub enum TransformEv {
+    /// Anticlockwise, in degrees
+    Rotate(f32),
+    Scale(Vec2),
+}
+
+impl Display for TransformEv {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rotate(angle) => write!(f, "Rotated by {angle:.1}°"),
+            Self::Scale(scale) => write!(f, "Scaled by {:.2}×{:.2}", scale.x, scale.y),
+        }
+    }
+}
+
+impl TransformEv {
+    #[must_use]
+    pub fn transform_coord(self, coord: Vec2, pivot: Vec2) -> Vec2 {
+        match self {
+            Self::Rotate(angle) => {
+                pivot + Vec2::from_angle(angle.to_radians()).rotate(coord - pivot)
+            }
+            Self::Scale(scale) => pivot + (coord - pivot) * scale,
+        }
+    }
+    pub fn apply(self, pla: &mut PlaComponent, pivot: Vec2) {
+        for node in &mut pla.nodes {
+            node.0 = self
+                .transform_coord(node.0.as_vec2(), pivot)
+                .round()
+                .as_ivec2();
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Component)]
+pub enum TransformHandle {
+    Rotate,
+    Scale(Pivot),
+}
+
+impl TransformHandle {
+    pub const ALL: [Self; 5] = [
+        Self::Rotate,
+        Self::Scale(Pivot::TopLeft),
+        Self::Scale(Pivot::TopRight),
+        Self::Scale(Pivot::BottomLeft),
+        Self::Scale(Pivot::BottomRight),
+    ];
+}
+
+#[derive(Component)]
+pub struct PivotMarker;
+
+#[derive(Debug, Clone, Component)]
+pub struct TransformDragData {
+    pub pivot: Vec2,
+    pub start: Vec2,
+    pub op: Option<TransformEv>,
+}
+
+fn selected_nodes<'a, I: IntoIterator<Item = &'a PlaComponent>>(selected: I) -> Vec<Vec2> {
+    selected
+        .into_iter()
+        .flat_map(|pla| pla.nodes.iter().map(|a| a.0.as_vec2()))
+        .collect()
+}
+
+#[tracing::instrument(skip_all)]
+pub fn transform_handles_sy(
+    mut commands: Commands,
+    selected: Query<&PlaComponent, With<SelectedComponent>>,
+    handles: Query<(Entity, &TransformHandle)>,
+    pivot_markers: Query<Entity, With<PivotMarker>>,
+    settings: Res<TransformSettings>,
+    zoom: Res<Zoom>,
+    misc_settings: Res<MiscSettings>,
+) {
+    let nodes = selected_nodes(&selected);
+    if nodes.is_empty() {
+        for e in handles.iter().map(|(e, _)| e).chain(&pivot_markers) {
+            commands.entity(e).despawn();
+        }
+        return;
+    }
+    let (min, max) = bounding_box(&nodes);
+    let handles = handles
+        .iter()
+        .map(|(e, handle)| (*handle, e))
+        .collect::<HashMap<_, _>>();
+    for handle in TransformHandle::ALL {
+        let pos = match handle {
+            TransformHandle::Rotate => Vec2::new(
+                (min.x + max.x) / 2.0,
+                max.y + 4096.0 / zoom.0.exp2() * misc_settings.big_handle_size,
+            ),
+            TransformHandle::Scale(corner) => corner.resolve(&[min, max], Vec2::ZERO),
+        };
+        let circle = make_circle(&zoom, pos, misc_settings.big_handle_size, BLACK.into());
+        if let Some(e) = handles.get(&handle) {
+            commands.entity(*e).remove::<Aabb>().insert(circle);
+        } else {
+            commands.spawn((circle, handle, Pickable::default(), RayCastBackfaces));
+        }
+    }
+
+    let pivot = settings.pivot.resolve(&nodes, settings.cursor_pivot);
+    let marker = make_circle(
+        &zoom,
+        pivot,
+        misc_settings.small_handle_size,
+        FUCHSIA.into(),
+    );
+    if let Ok(e) = pivot_markers.single() {
+        commands.entity(e).remove::<Aabb>().insert(marker);
+    } else {
+        commands.spawn((marker, PivotMarker));
+    }
+}
+
+pub fn clear_transform_handles_sy(
+    mut commands: Commands,
+    handles: Query<Entity, Or<(With<TransformHandle>, With<PivotMarker>)>>,
+) {
+    for e in &handles {
+        commands.entity(e).despawn();
+    }
+}
+
+#[tracing::instrument(skip_all)]
+pub fn on_set_cursor_pivot(
+    trigger: Trigger<Pointer<Click2>>,
+    mut settings: ResMut<TransformSettings>,
+    mut status: ResMut<Status>,
+    state: Res<State<EditorState>>,
+    pointer_within_tilemap: Option<Res<PointerWithinTilemap>>,
+) {
+    if pointer_within_tilemap.is_none()
+        || trigger.button != PointerButton::Middle
+        || **state != EditorState::Transforming
+    {
+        return;
+    }
+    settings.cursor_pivot = trigger.hit.position.unwrap_or_default().xy().round();
+    settings.pivot = Pivot::Cursor;
+    status.set(format!(
+        "Set pivot to {}, {}",
+        settings.cursor_pivot.x, -settings.cursor_pivot.y
+    ));
+}
+
+#[tracing::instrument(skip_all)]
+pub fn on_handle_drag_start(
+    trigger: Trigger<Pointer<DragStart>>,
+    mut commands: Commands,
+    handles: Query<(), With<TransformHandle>>,
+    selected: Query<&PlaComponent, With<SelectedComponent>>,
+    settings: Res<TransformSettings>,
+    mouse_pos_world: Res<MousePosWorld>,
+    state: Res<State<EditorState>>,
+    pointer_within_tilemap: Option<Res<PointerWithinTilemap>>,
+) {
+    if pointer_within_tilemap.is_none()
+        || trigger.button != PointerButton::Secondary
+        || **state != EditorState::Transforming
+        || !handles.contains(trigger.target())
+    {
+        return;
+    }
+    let pivot = settings
+        .pivot
+        .resolve(&selected_nodes(&selected), settings.cursor_pivot);
+    info!("Started transform");
+    commands.entity(trigger.target()).insert(TransformDragData {
+        pivot,
+        start: **mouse_pos_world,
+        op: None,
+    });
+}
+
+#[tracing::instrument(skip_all)]
+pub fn on_handle_drag(
+    trigger: Trigger<Pointer<Drag>>,
+    mut commands: Commands,
+    mut handles: Query<(&TransformHandle, &mut TransformDragData)>,
+    selected: Query<(Entity, &PlaComponent), With<SelectedComponent>>,
+    settings: Res<TransformSettings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_pos_world: Res<MousePosWorld>,
+    mut status: ResMut<Status>,
+) {
+    if trigger.button != PointerButton::Secondary {
+        return;
+    }
+    let Ok((handle, mut data)) = handles.get_mut(trigger.target()) else {
+        return;
+    };
+    let (from, to) = (data.start - data.pivot, **mouse_pos_world - data.pivot);
+    let op = match handle {
+        TransformHandle::Rotate => {
+            TransformEv::Rotate(settings.snap(from.angle_to(to).to_degrees()))
+        }
+        TransformHandle::Scale(_) => {
+            if settings.uniform || keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+                TransformEv::Scale(Vec2::splat(to.length() / from.length().max(f32::EPSILON)))
+            } else {
+                let ratio = |from: f32, to: f32| if from.abs() < 1.0 { 1.0 } else { to / from };
+                TransformEv::Scale(Vec2::new(ratio(from.x, to.x), ratio(from.y, to.y)))
+            }
+        }
+    };
+    data.op = Some(op);
+    for (e, pla) in &selected {
+        let mut pla = pla.to_owned();
+        op.apply(&mut pla, data.pivot);
+        commands.entity(e).trigger(RenderEv(Some(pla)));
+    }
+    status.set(op.to_string());
+}
+
+#[tracing::instrument(skip_all)]
+pub fn on_handle_drag_end(
+    trigger: Trigger<Pointer<DragEnd>>,
+    mut commands: Commands,
+    handles: Query<&TransformDragData, With<TransformHandle>>,
+) {
+    if trigger.button != PointerButton::Secondary {
+        return;
+    }
+    let Ok(data) = handles.get(trigger.target()) else {
+        return;
+    };
+    if let Some(op) = data.op {
+        commands.trigger(op);
+    }
+    info!("Ended transform");
+    commands
+        .entity(trigger.target())
+        .remove::<TransformDragData>();
+}
+
+#[tracing::instrument(skip_all)]
+pub fn on_transform(
+    trigger: Trigger<TransformEv>,
+    mut commands: Commands,
+    mut selected: Query<(Entity, &mut PlaComponent), With<SelectedComponent>>,
+    settings: Res<TransformSettings>,
+    mut status: ResMut<Status>,
+) {
+    let op = *trigger.event();
+    let nodes = selected_nodes(selected.iter().map(|(_, pla)| pla));
+    if nodes.is_empty() {
+        status.set("Nothing selected to transform");
+        return;
+    }
+    let pivot = settings.pivot.resolve(&nodes, settings.cursor_pivot);
+    info!(?op, ?pivot, "Transforming selected components");
+
+    let histories = selected
+        .iter_mut()
+        .map(|(e, mut pla)| {
+            let before = pla.to_owned();
+            op.apply(&mut pla, pivot);
+            commands.entity(e).trigger(RenderEv::default());
+            HistoryEntry::Component {
+                e,
+                before: Some(before.into()),
+                after: Some(pla.to_owned().into()),
+            }
+        })
+        .collect::<Vec<_>>();
+    status.set(format!("{op} ({} components)", histories.len()));
+    commands.trigger(HistoryEv::NewHistory(histories));
+}
+
+pub struct TransformComponentPlugin;
+impl Plugin for TransformComponentPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TransformSettings>()
+            .add_systems(
+                Update,
+                transform_handles_sy.run_if(in_state(EditorState::Transforming)),
+            )
+            .add_systems(
+                OnExit(EditorState::Transforming),
+                clear_transform_handles_sy,
+            )
+            .add_observer(on_set_cursor_pivot)
+            .add_observer(on_handle_drag_start)
+            .add_observer(on_handle_drag)
+            .add_observer(on_handle_drag_end)
+            .add_observer(on_transform);
+    }
+}
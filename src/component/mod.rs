@@ -1,8 +1,12 @@
 use bevy::prelude::*;
 
-use crate::component::{pla2::PlaComponent, skin::Skin};
+use crate::{
+    component::{pla2::PlaComponent, skin::Skin},
+    ui::map::zoom::SkinZoom,
+};
 
 pub mod pla2;
+pub mod presets;
 pub mod skin;
 
 pub mod actions;
@@ -11,9 +15,9 @@ pub mod panels;
 pub mod tools;
 
 #[must_use]
-pub fn make_component(pla: PlaComponent, skin: &Skin) -> impl Bundle {
+pub fn make_component(pla: PlaComponent, skin: &Skin, zoom: SkinZoom) -> impl Bundle {
     (
-        pla.get_shape(skin),
+        pla.get_shape(skin, zoom),
         pla,
         Pickable::default(),
         RayCastBackfaces,
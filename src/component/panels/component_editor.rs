@@ -1,16 +1,26 @@
+use std::{collections::HashSet, sync::LazyLock};
+
 use bevy::prelude::*;
 use bevy_egui::egui;
 use egui_extras::{Column, TableBuilder};
+use egui_notify::ToastLevel;
+use eyre::eyre;
 use itertools::Itertools;
+use lazy_regex::{lazy_regex, Regex};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     component::{
         actions::rendering::RenderEv,
-        pla2::{ComponentType, MCCoords},
+        pla2::{ComponentType, EditorCoords, MCCoords, PlaComponent},
+        presets::ComponentPreset,
     },
     history::{HistoryEntry, HistoryEv},
-    ui::panel::dock::{open_dock_window, DockLayout, DockWindow, PanelParams},
+    project::settings::{slugify, IdScheme},
+    ui::{
+        notif::{NotifLogRwLockExt, NOTIF_LOG},
+        panel::dock::{open_dock_window, DockLayout, DockWindow, PanelParams},
+    },
 };
 
 #[derive(Clone, Copy, Serialize, Deserialize)]
@@ -19,6 +29,41 @@ pub struct ComponentEditor;
 #[derive(Clone, Copy, Event)]
 pub struct OpenComponentEditorEv;
 
+#[derive(Clone, Copy)]
+struct NodeDragPayload(usize);
+
+static TP_PREFIX: LazyLock<Regex> = lazy_regex!(r"^.*\bto\s+");
+
+/// Parses one coordinate per line, accepting `x, z`, `x y z` and the output of Minecraft's `/tp`
+pub fn parse_coords(text: &str) -> eyre::Result<Vec<MCCoords>> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let line = TP_PREFIX.replace(line.trim(), "");
+            let tokens = line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|a| !a.is_empty())
+                .collect::<Vec<_>>();
+            let start = tokens
+                .iter()
+                .rposition(|a| a.parse::<f32>().is_err())
+                .map_or(0, |a| a + 1);
+            let numbers = tokens[start..]
+                .iter()
+                .filter_map(|a| a.parse::<f32>().ok())
+                .collect::<Vec<_>>();
+            match *numbers.as_slice() {
+                [x, z] | [x, _, z] | [x, _, z, _, _] => Ok(MCCoords(Vec2::new(x, z))),
+                _ => Err(eyre!(
+                    "Line {}: could not parse coordinates from `{line}`",
+                    i + 1
+                )),
+            }
+        })
+        .collect()
+}
+
 impl DockWindow for ComponentEditor {
     fn title(self) -> String {
         "Component".into()
@@ -29,41 +74,119 @@ impl DockWindow for ComponentEditor {
             commands,
             skin,
             namespaces,
+            paste_coords,
+            presets,
+            preset_name,
+            manifest,
+            id_draft,
             ..
         } = params;
+        let selected_e = queries.p0().single().map(|(e, _)| e).ok();
+        let taken = selected_e
+            .map(|e| {
+                queries
+                    .p1()
+                    .iter()
+                    .filter(|(e2, _)| *e2 != e)
+                    .map(|(_, a)| (a.namespace.clone(), a.id.clone()))
+                    .collect::<HashSet<_>>()
+            })
+            .unwrap_or_default();
+        let taken_in = |ns: &str| {
+            taken
+                .iter()
+                .filter(|(ns2, _)| ns2 == ns)
+                .map(|(_, id)| id.as_str())
+                .collect::<HashSet<_>>()
+        };
+
         let mut selected = queries.p0();
         if selected.is_empty() {
             ui.heading("Select a component...");
             return;
         }
-        let (e, mut component_data) = selected.single_mut().unwrap();
+        let Ok((e, mut component_data)) = selected.single_mut() else {
+            ui.heading(format!("{} components selected", selected.iter().count()));
+            return;
+        };
         let old_data = component_data.to_owned();
+        let mut id_error = None;
 
         ui.heading("Edit component data");
         ui.end_row();
 
         ui.horizontal(|ui| {
-            egui::ComboBox::from_label("ns.")
-                .selected_text(&component_data.namespace)
-                .width(25.0)
-                .show_ui(ui, |ui| {
-                    ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
-                    for (ns, vis) in &namespaces.visibilities {
-                        if !vis {
-                            continue;
+            let locked = namespaces.locked.contains(&component_data.namespace);
+            ui.add_enabled_ui(!locked, |ui| {
+                egui::ComboBox::from_label("ns.")
+                    .selected_text(&component_data.namespace)
+                    .width(25.0)
+                    .show_ui(ui, |ui| {
+                        ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+                        for (ns, vis) in &namespaces.visibilities {
+                            if !vis || namespaces.locked.contains(ns) {
+                                continue;
+                            }
+                            ui.selectable_value(&mut component_data.namespace, ns.to_owned(), ns);
                         }
-                        ui.selectable_value(&mut component_data.namespace, ns.to_owned(), ns);
-                    }
-                });
+                    });
+            })
+            .response
+            .on_disabled_hover_text("The namespace is locked");
+            if component_data.namespace != old_data.namespace
+                && taken.contains(&(component_data.namespace.clone(), component_data.id.clone()))
+            {
+                NOTIF_LOG.push(
+                    format!(
+                        "The id {} is already used in namespace {}",
+                        component_data.id, component_data.namespace
+                    ),
+                    ToastLevel::Warning,
+                );
+                component_data.namespace.clone_from(&old_data.namespace);
+            }
             component_data
                 .namespace
                 .clone_into(&mut namespaces.prev_used);
-            ui.add(
-                egui::TextEdit::singleline(&mut component_data.id)
-                    .hint_text("id")
-                    .desired_width(f32::INFINITY),
-            );
+
+            // The id is edited as a draft, and only changed once it is valid
+            if id_draft.as_ref().is_none_or(|(e2, _)| *e2 != e) {
+                **id_draft = Some((e, component_data.id.clone()));
+            }
+            let Some((_, draft)) = &mut **id_draft else {
+                return;
+            };
+            if ui.small_button("🔄").on_hover_text("Generate id").clicked() {
+                *draft =
+                    manifest.generate_id(&component_data, &taken_in(&component_data.namespace));
+                draft.clone_into(&mut component_data.id);
+            }
+            let changed = ui
+                .add(
+                    egui::TextEdit::singleline(draft)
+                        .hint_text("id")
+                        .desired_width(f32::INFINITY),
+                )
+                .changed();
+            if draft.is_empty() {
+                id_error = Some("The id is empty".to_owned());
+            } else if taken_in(&component_data.namespace).contains(draft.as_str()) {
+                id_error = Some(format!(
+                    "The id is already used in namespace {}",
+                    component_data.namespace
+                ));
+            } else if changed {
+                draft.clone_into(&mut component_data.id);
+            } else {
+                component_data.id.clone_into(draft);
+            }
         });
+        if let Some(id_error) = id_error {
+            ui.colored_label(
+                egui::Color32::RED,
+                format!("{id_error}, so it has not been changed"),
+            );
+        }
         ui.end_row();
 
         ui.add(
@@ -71,6 +194,13 @@ impl DockWindow for ComponentEditor {
                 .hint_text("Displayed as")
                 .desired_width(f32::INFINITY),
         );
+        if manifest.id_scheme == IdScheme::Slug
+            && component_data.display_name != old_data.display_name
+            && is_slug_of(&component_data.id, &old_data.display_name)
+        {
+            component_data.id =
+                manifest.generate_id(&component_data, &taken_in(&component_data.namespace));
+        }
         ui.end_row();
 
         ui.add(
@@ -116,6 +246,26 @@ impl DockWindow for ComponentEditor {
 
         ui.add(egui::Slider::new(&mut component_data.layer, -10.0..=10.0).text("Layer"));
         ui.end_row();
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut **preset_name).hint_text("Preset name"));
+            if ui
+                .add_enabled(!preset_name.is_empty(), egui::Button::new("Save as preset"))
+                .clicked()
+            {
+                presets.0.insert(
+                    preset_name.to_owned(),
+                    ComponentPreset::from_component(&component_data, component_type),
+                );
+                if presets.save().is_ok() {
+                    NOTIF_LOG.push(
+                        format!("Saved preset {}", **preset_name),
+                        ToastLevel::Success,
+                    );
+                }
+                preset_name.clear();
+            }
+        });
+        ui.end_row();
         ui.separator();
         if component_data.get_skin_type(skin) == ComponentType::Line {
             if ui.button("Reverse direction").clicked() {
@@ -125,58 +275,197 @@ impl DockWindow for ComponentEditor {
             ui.separator();
         }
 
+        match component_type {
+            ComponentType::Line => {
+                ui.label(format!("Length: {:.1} blocks", component_data.length()));
+            }
+            ComponentType::Area => {
+                ui.label(format!("Area: {:.1} blocks²", component_data.area()));
+                ui.label(format!(
+                    "Perimeter: {:.1} blocks",
+                    component_data.perimeter()
+                ));
+            }
+            ComponentType::Point => {}
+        }
         ui.heading("Position data");
-        let is_line = component_data.get_skin_type(skin) == ComponentType::Line;
+        let is_line = component_type == ComponentType::Line;
+        let is_point = component_type == ComponentType::Point;
+        let min_nodes = if component_type == ComponentType::Area {
+            3
+        } else {
+            2
+        };
+        let num_nodes = component_data.nodes.len();
+        let mut insert = None;
+        let mut delete = None;
+        let mut reorder = None;
         TableBuilder::new(ui)
+            .column(Column::auto().at_least(10.0))
             .columns(Column::auto().at_least(50.0), 2)
+            .columns(Column::auto().at_least(10.0), 2)
             .cell_layout(egui::Layout::default().with_cross_align(egui::Align::RIGHT))
             .header(10.0, |mut header| {
+                header.col(|_| ());
                 header.col(|ui| {
                     ui.label("X");
                 });
                 header.col(|ui| {
-                    ui.label("Y");
+                    ui.label("Z");
                 });
             })
             .body(|mut body| {
-                for (i, a) in component_data.nodes.iter().enumerate() {
-                    let a = MCCoords::from(*a);
+                for (i, node) in component_data.nodes.iter_mut().enumerate() {
+                    let mut coords = MCCoords::from(*node);
                     let colour = if i == 0 && is_line {
                         egui::Color32::GREEN
-                    } else if i == component_data.nodes.len() - 1 && is_line {
+                    } else if i == num_nodes - 1 && is_line {
                         egui::Color32::RED
                     } else {
                         egui::Color32::WHITE
                     };
-                    body.row(10.0, |mut row| {
+                    body.row(20.0, |mut row| {
+                        row.col(|ui| {
+                            ui.add_enabled_ui(!is_point, |ui| {
+                                ui.dnd_drag_source(
+                                    egui::Id::new(("position_data", i)),
+                                    NodeDragPayload(i),
+                                    |ui| ui.label("☰"),
+                                );
+                            });
+                        });
+                        for axis in [&mut coords.0.x, &mut coords.0.y] {
+                            row.col(|ui| {
+                                ui.visuals_mut().override_text_color = Some(colour);
+                                ui.add(egui::DragValue::new(axis).speed(1.0).fixed_decimals(0));
+                            });
+                        }
                         row.col(|ui| {
-                            ui.label(
-                                egui::RichText::new(a.0.x.to_string())
-                                    .color(colour)
-                                    .monospace(),
-                            );
+                            if ui
+                                .add_enabled(!is_point, egui::Button::new("➕").small())
+                                .on_hover_text("Insert node after")
+                                .clicked()
+                            {
+                                insert = Some(i);
+                            }
                         });
                         row.col(|ui| {
-                            ui.label(
-                                egui::RichText::new(a.0.y.to_string())
-                                    .color(colour)
-                                    .monospace(),
-                            );
+                            if ui
+                                .add_enabled(
+                                    !is_point && num_nodes > min_nodes,
+                                    egui::Button::new("❌")
+                                        .small()
+                                        .fill(egui::Color32::DARK_RED),
+                                )
+                                .on_hover_text("Delete node")
+                                .clicked()
+                            {
+                                delete = Some(i);
+                            }
                         });
+                        if let Some(payload) =
+                            row.response().dnd_release_payload::<NodeDragPayload>()
+                        {
+                            reorder = Some((payload.0, i));
+                        }
                     });
+                    *node = MCCoords(coords.0.round()).into();
+                }
+            });
+        if let Some(i) = insert {
+            let this = component_data.nodes[i].0;
+            let next = component_data
+                .nodes
+                .get(i + 1)
+                .or(if component_type == ComponentType::Area {
+                    component_data.nodes.first()
+                } else {
+                    None
+                })
+                .map_or(this + IVec2::X, |next| (this + next.0) / 2);
+            component_data.nodes.insert(i + 1, next.into());
+        }
+        if let Some(i) = delete {
+            component_data.nodes.remove(i);
+        }
+        if let Some((from, to)) = reorder {
+            let node = component_data.nodes.remove(from);
+            component_data.nodes.insert(to, node);
+        }
+
+        egui::CollapsingHeader::new("Paste coordinates").show(ui, |ui| {
+            ui.add(
+                egui::TextEdit::multiline(&mut **paste_coords)
+                    .hint_text("x, z\n...")
+                    .desired_width(f32::INFINITY),
+            );
+            ui.label(
+                "One coordinate per line, either as \"x, z\", \"x, y, z\", or the output of /tp",
+            );
+            let parsed = parse_coords(&**paste_coords);
+            if let Err(e) = &parsed {
+                ui.colored_label(egui::Color32::RED, e.to_string());
+            }
+            ui.horizontal(|ui| {
+                let num_parsed = parsed.as_ref().map_or(0, Vec::len);
+                if ui
+                    .add_enabled(
+                        if is_point {
+                            num_parsed == 1
+                        } else {
+                            num_parsed >= 2
+                        },
+                        egui::Button::new("Replace nodes"),
+                    )
+                    .clicked()
+                {
+                    if let Ok(parsed) = &parsed {
+                        component_data.nodes = parsed.iter().map(|a| (*a).into()).collect();
+                    }
+                    paste_coords.clear();
+                }
+                if ui
+                    .add_enabled(
+                        !is_point && num_parsed > 0,
+                        egui::Button::new("Append nodes"),
+                    )
+                    .clicked()
+                {
+                    if let Ok(parsed) = &parsed {
+                        component_data
+                            .nodes
+                            .extend(parsed.iter().map(|a| EditorCoords::from(*a)));
+                    }
+                    paste_coords.clear();
                 }
             });
+        });
 
+        if component_data.nodes != old_data.nodes {
+            commands.entity(e).trigger(RenderEv::default());
+        }
         if *component_data != old_data {
-            commands.trigger(HistoryEv::one_history(HistoryEntry::Component {
+            commands.trigger(HistoryEv::Coalesce(vec![HistoryEntry::Component {
                 e,
                 before: Some(old_data.into()),
                 after: Some(component_data.to_owned().into()),
-            }));
+            }]));
         }
     }
 }
 
+/// Whether `id` could have been generated from `name` by the slug id scheme, in which case it
+/// follows the display name as it is edited. Ids of unnamed components are replaced once named
+fn is_slug_of(id: &str, name: &str) -> bool {
+    let slug = slugify(name);
+    slug.is_empty()
+        || id == slug
+        || id
+            .strip_prefix(&slug)
+            .and_then(|a| a.strip_prefix('-'))
+            .is_some_and(|a| a.parse::<u32>().is_ok())
+}
+
 pub fn on_component_editor(
     _trigger: Trigger<OpenComponentEditorEv>,
     mut state: ResMut<DockLayout>,
@@ -1,12 +1,31 @@
 use bevy::prelude::*;
+use bevy_egui::EguiContextPass;
+
+use crate::state::IntoSystemConfigExt;
 
 pub mod component_editor;
 pub mod component_list;
+pub mod skin_editor;
+pub mod transform_editor;
+pub mod type_migration;
 
 pub struct ComponentPanelsPlugin;
 impl Plugin for ComponentPanelsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_observer(component_editor::on_component_editor)
-            .add_observer(component_list::on_component_list);
+        app.init_resource::<skin_editor::SkinDraft>()
+            .add_observer(component_editor::on_component_editor)
+            .add_observer(component_list::on_component_list)
+            .add_observer(transform_editor::on_transform_editor)
+            .add_observer(skin_editor::on_skin_editor)
+            .add_observer(type_migration::on_type_migration)
+            .add_systems(
+                Update,
+                (
+                    type_migration::notify_unknown_types_sy,
+                    skin_editor::apply_skin_draft_sy,
+                )
+                    .run_if_not_loading(),
+            )
+            .add_systems(EguiContextPass, skin_editor::skin_editor_dialog);
     }
 }
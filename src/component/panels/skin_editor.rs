@@ -0,0 +1,609 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use egui_file_dialog::FileDialog;
+use egui_notify::ToastLevel;
+use hex_color::HexColor;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::{
+        actions::rendering::RenderEv,
+        pla2::{ComponentType, PlaComponent},
+        skin::{AreaStyle, LineStyle, PointStyle, Skin, SkinComponent},
+    },
+    file::save_json,
+    history::HistoryEntry,
+    init::load_skin::fallback_skin,
+    ui::{
+        file_dialogs::FileDialogs,
+        notif::{NotifLogRwLockExt, NOTIF_LOG},
+        panel::dock::{open_dock_window, DockLayout, DockWindow, PanelParams},
+        popup::Popup,
+    },
+};
+
+#[derive(Clone, PartialEq, Eq, Event)]
+pub enum SkinEditorEv {
+    Open,
+    Export,
+    /// Deletes the type at this index, if it still has this name
+    DeleteType(usize, String),
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct SkinEditor;
+
+/// Copy of the skin being edited, written back to [`Skin`] by [`apply_skin_draft_sy`]
+#[derive(Resource, Default)]
+pub struct SkinDraft(pub Option<Skin>);
+
+enum TypeAction {
+    Up(usize),
+    Down(usize),
+    Delete(usize),
+    Rename(usize, String),
+}
+
+impl DockWindow for SkinEditor {
+    fn title(self) -> String {
+        "Skin Editor".into()
+    }
+    fn ui(self, params: &mut PanelParams, ui: &mut egui::Ui) {
+        let PanelParams {
+            queries,
+            skin,
+            skin_draft,
+            namespaces,
+            rename_type,
+            commands,
+            popups,
+            history,
+            ..
+        } = params;
+        let mut changed = false;
+        // Only flag the draft as changed on edits, otherwise the map re-renders every frame
+        let skin_mut = skin_draft
+            .bypass_change_detection()
+            .0
+            .get_or_insert_with(|| (**skin).clone());
+        let query = queries.p1();
+        let type_counts = query
+            .iter()
+            .filter(|(_, a)| !namespaces.locked.contains(&a.namespace))
+            .map(|(_, a)| a.ty.as_str())
+            .counts();
+
+        ui.label(format!(
+            "{} (v{}), licensed under {}",
+            skin_mut.name, skin_mut.version, skin_mut.licence
+        ));
+        ui.horizontal(|ui| {
+            for ty in [
+                ComponentType::Point,
+                ComponentType::Line,
+                ComponentType::Area,
+            ] {
+                if ui.button(format!("Add {ty:?}")).clicked() {
+                    changed |= Self::add_type(skin_mut, ty);
+                }
+            }
+            if ui.button("Export JSON").clicked() {
+                commands.trigger(SkinEditorEv::Export);
+            }
+        });
+        ui.label("Types lower down this list are drawn above types higher up");
+        ui.separator();
+
+        let len = skin_mut.types.len();
+        let names = skin_mut.types.iter().map(|a| a.name().to_owned()).counts();
+        let mut action = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, ty) in skin_mut.types.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(i != 0, egui::Button::new("⬆")).clicked() {
+                        action = Some(TypeAction::Up(i));
+                    }
+                    if ui
+                        .add_enabled(i + 1 != len, egui::Button::new("⬇"))
+                        .clicked()
+                    {
+                        action = Some(TypeAction::Down(i));
+                    }
+                    if ui
+                        .add(egui::Button::new("❌").fill(egui::Color32::DARK_RED))
+                        .clicked()
+                    {
+                        action = Some(TypeAction::Delete(i));
+                    }
+                    let label = ty.widget_text(ui, &egui::TextStyle::Body);
+                    ui.label(label);
+                    ui.label(
+                        egui::RichText::new(format!("{:?}", ty.get_type()))
+                            .text_style(egui::TextStyle::Small),
+                    );
+                });
+                egui::CollapsingHeader::new("Edit")
+                    .id_salt(("skin_type", i))
+                    .show(ui, |ui| {
+                        ui.push_id(i, |ui| {
+                            let count = type_counts.get(ty.name()).copied().unwrap_or_default();
+                            if let Some(new_name) =
+                                Self::name_ui(ui, i, ty.name(), count, &names, rename_type)
+                            {
+                                action = Some(TypeAction::Rename(i, new_name));
+                            }
+                            changed |= Self::type_ui(ui, ty);
+                        });
+                    });
+                ui.separator();
+            }
+        });
+
+        // Deleting waits for confirmation, which then edits the draft in `on_skin_editor`
+        let acted = action
+            .as_ref()
+            .is_some_and(|a| !matches!(a, TypeAction::Delete(_)));
+        match action {
+            Some(TypeAction::Up(i)) => skin_mut.types.swap(i - 1, i),
+            Some(TypeAction::Down(i)) => skin_mut.types.swap(i, i + 1),
+            Some(TypeAction::Delete(i)) => {
+                let name = skin_mut.types[i].name().to_owned();
+                let count = type_counts.get(name.as_str()).copied().unwrap_or_default();
+                popups.add(Popup::base_confirm(
+                    "delete_skin_type",
+                    format!("Delete {name}?"),
+                    if count == 0 {
+                        "No loaded components use this type".to_owned()
+                    } else {
+                        format!("{count} loaded components use this type, and will not be drawn until they are given another type")
+                    },
+                    SkinEditorEv::DeleteType(i, name),
+                ));
+            }
+            Some(TypeAction::Rename(i, new_name)) => {
+                let old_name = std::mem::replace(skin_mut.types[i].name_mut(), new_name.clone());
+                // The skin edit can't be undone, so neither is the change to the components.
+                // Past undo steps are updated as well so that undoing them keeps the new type
+                let history = &mut **history;
+                for entry in history
+                    .undo_stack
+                    .iter_mut()
+                    .chain(&mut history.redo_stack)
+                    .flatten()
+                {
+                    if let HistoryEntry::Component { before, after, .. } = entry {
+                        for pla in before.iter_mut().chain(after) {
+                            if pla.ty == old_name && !namespaces.locked.contains(&pla.namespace) {
+                                new_name.clone_into(&mut pla.ty);
+                            }
+                        }
+                    }
+                }
+                let changed_namespaces = query
+                    .iter()
+                    .filter(|(_, a)| a.ty == old_name && !namespaces.locked.contains(&a.namespace))
+                    .map(|(e, pla)| {
+                        commands
+                            .entity(e)
+                            .insert(PlaComponent {
+                                ty: new_name.to_owned(),
+                                ..pla.to_owned()
+                            })
+                            .trigger(RenderEv::default());
+                        pla.namespace.clone()
+                    })
+                    .collect::<Vec<_>>();
+                if !changed_namespaces.is_empty() {
+                    NOTIF_LOG.push(
+                        format!(
+                            "Changed the type of {} components to {new_name}",
+                            changed_namespaces.len()
+                        ),
+                        ToastLevel::Success,
+                    );
+                    namespaces.dirty.extend(changed_namespaces);
+                }
+            }
+            None => {}
+        }
+        if changed || acted {
+            skin_mut.edited = true;
+            skin_draft.set_changed();
+        }
+    }
+}
+
+impl SkinEditor {
+    fn add_type(skin: &mut Skin, ty: ComponentType) -> bool {
+        let Some(mut new) = fallback_skin()
+            .types
+            .into_iter()
+            .find(|a| a.get_type() == ty)
+        else {
+            return false;
+        };
+        let mut name = format!("new{ty:?}");
+        let mut n = 1;
+        while skin.get_type(&name).is_some() {
+            n += 1;
+            name = format!("new{ty:?}{n}");
+        }
+        *new.name_mut() = name;
+        skin.types.push(new);
+        true
+    }
+
+    /// Shows the type's name, returning the new name once a rename is confirmed
+    fn name_ui(
+        ui: &mut egui::Ui,
+        i: usize,
+        name: &str,
+        count: usize,
+        names: &HashMap<String, usize>,
+        rename_type: &mut Option<(usize, String)>,
+    ) -> Option<String> {
+        let mut renamed = None;
+        let mut done = false;
+        match rename_type {
+            Some((j, new_name)) if *j == i => {
+                ui.horizontal(|ui| {
+                    ui.label("Name");
+                    ui.text_edit_singleline(new_name);
+                });
+                let taken = new_name != name && names.contains_key(new_name.as_str());
+                if taken {
+                    ui.colored_label(egui::Color32::RED, "Another type has the same name");
+                } else if count != 0 {
+                    ui.colored_label(
+                        egui::Color32::YELLOW,
+                        format!(
+                            "{count} loaded components will also be changed to this type. This can't be undone"
+                        ),
+                    );
+                }
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            !taken && !new_name.is_empty() && new_name != name,
+                            egui::Button::new("Rename"),
+                        )
+                        .clicked()
+                    {
+                        renamed = Some(new_name.to_owned());
+                    }
+                    done = ui.button("Cancel").clicked() || renamed.is_some();
+                });
+            }
+            _ => {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Name: {name}"));
+                    if ui.small_button("✏").on_hover_text("Rename").clicked() {
+                        *rename_type = Some((i, name.to_owned()));
+                    }
+                });
+                if names.get(name).is_some_and(|a| *a > 1) {
+                    ui.colored_label(egui::Color32::RED, "Another type has the same name");
+                }
+            }
+        }
+        if done {
+            *rename_type = None;
+        }
+        renamed
+    }
+
+    fn type_ui(ui: &mut egui::Ui, ty: &mut SkinComponent) -> bool {
+        if !ty.tags().is_empty() {
+            ui.label(format!("Tags: {}", ty.tags().join(", ")));
+        }
+        match ty {
+            SkinComponent::Point { styles, .. } => Self::bands_ui(ui, styles, point_style_ui),
+            SkinComponent::Line { styles, .. } => Self::bands_ui(ui, styles, line_style_ui),
+            SkinComponent::Area { styles, .. } => Self::bands_ui(ui, styles, area_style_ui),
+        }
+    }
+
+    fn bands_ui<T, F: Fn(&mut egui::Ui, &mut T) -> bool>(
+        ui: &mut egui::Ui,
+        styles: &mut HashMap<String, Vec<T>>,
+        style_ui: F,
+    ) -> bool {
+        let mut changed = false;
+        for (band, styles) in styles.iter_mut().sorted_by_key(|(zl, _)| {
+            zl.split('-')
+                .next()
+                .and_then(|a| a.parse::<u8>().ok())
+                .unwrap_or(u8::MAX)
+        }) {
+            ui.collapsing(format!("Zoom {band}"), |ui| {
+                for (i, style) in styles.iter_mut().enumerate() {
+                    if i != 0 {
+                        ui.separator();
+                    }
+                    changed |= style_ui(ui, style);
+                }
+            });
+        }
+        changed
+    }
+
+    #[must_use]
+    pub fn export_dialog(name: &str) -> FileDialog {
+        FileDialog::new()
+            .title(&format!("Export skin {name}"))
+            .default_file_name(&format!("{name}.json"))
+            .storage(FileDialogs::load_storage())
+    }
+}
+
+fn colour_ui(ui: &mut egui::Ui, label: &str, colour: &mut Option<HexColor>) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label(label);
+        if let Some(c) = colour {
+            let mut colour32 = egui::Color32::from_rgba_unmultiplied(c.r, c.g, c.b, c.a);
+            if egui::color_picker::color_edit_button_srgba(
+                ui,
+                &mut colour32,
+                egui::color_picker::Alpha::OnlyBlend,
+            )
+            .changed()
+            {
+                let [r, g, b, a] = colour32.to_srgba_unmultiplied();
+                *c = HexColor::rgba(r, g, b, a);
+                changed = true;
+            }
+            if ui.small_button("❌").clicked() {
+                *colour = None;
+                changed = true;
+            }
+        } else if ui.small_button("➕").clicked() {
+            *colour = Some(HexColor::BLACK);
+            changed = true;
+        }
+    });
+    changed
+}
+
+fn number_ui(ui: &mut egui::Ui, label: &str, value: &mut f32) -> bool {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add(
+            egui::DragValue::new(value)
+                .speed(0.1)
+                .range(0.0..=f32::INFINITY),
+        )
+        .changed()
+    })
+    .inner
+}
+
+fn dash_ui(ui: &mut egui::Ui, dash: &mut Option<Vec<f32>>) -> bool {
+    let mut changed = false;
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Dash");
+        let Some(segments) = dash else {
+            if ui.small_button("➕").clicked() {
+                *dash = Some(vec![1.0, 1.0]);
+                changed = true;
+            }
+            return;
+        };
+        for segment in segments.iter_mut() {
+            changed |= ui
+                .add(
+                    egui::DragValue::new(segment)
+                        .speed(0.1)
+                        .range(0.0..=f32::INFINITY),
+                )
+                .changed();
+        }
+        if ui.small_button("➕").clicked() {
+            segments.push(1.0);
+            changed = true;
+        }
+        if ui.small_button("➖").clicked() {
+            segments.pop();
+            if segments.is_empty() {
+                *dash = None;
+            }
+            changed = true;
+        }
+    });
+    changed
+}
+
+fn point_style_ui(ui: &mut egui::Ui, style: &mut PointStyle) -> bool {
+    match style {
+        PointStyle::Image {
+            zoom_multiplier,
+            size,
+            ..
+        } => {
+            ui.strong("Image");
+            number_ui(ui, "Zoom multiplier", zoom_multiplier)
+                | number_ui(ui, "Width", &mut size.x)
+                | number_ui(ui, "Height", &mut size.y)
+        }
+        PointStyle::Square {
+            zoom_multiplier,
+            colour,
+            border_radius,
+            size,
+            width,
+        } => {
+            ui.strong("Square");
+            number_ui(ui, "Zoom multiplier", zoom_multiplier)
+                | colour_ui(ui, "Colour", colour)
+                | number_ui(ui, "Size", size)
+                | number_ui(ui, "Border width", width)
+                | number_ui(ui, "Border radius", border_radius)
+        }
+        PointStyle::Text {
+            zoom_multiplier,
+            colour,
+            size,
+            ..
+        } => {
+            ui.strong("Text");
+            number_ui(ui, "Zoom multiplier", zoom_multiplier)
+                | colour_ui(ui, "Colour", colour)
+                | number_ui(ui, "Size", size)
+        }
+    }
+}
+
+fn line_style_ui(ui: &mut egui::Ui, style: &mut LineStyle) -> bool {
+    let heading = match style {
+        LineStyle::Fore { .. } => "Foreground",
+        LineStyle::Back { .. } => "Background",
+        LineStyle::Text { .. } => "Text",
+    };
+    ui.strong(heading);
+    match style {
+        LineStyle::Fore {
+            zoom_multiplier,
+            colour,
+            width,
+            dash,
+            unrounded,
+        }
+        | LineStyle::Back {
+            zoom_multiplier,
+            colour,
+            width,
+            dash,
+            unrounded,
+        } => {
+            number_ui(ui, "Zoom multiplier", zoom_multiplier)
+                | colour_ui(ui, "Colour", colour)
+                | number_ui(ui, "Width", width)
+                | dash_ui(ui, dash)
+                | ui.checkbox(unrounded, "Unrounded").changed()
+        }
+        LineStyle::Text {
+            zoom_multiplier,
+            colour,
+            arrow_colour,
+            size,
+            ..
+        } => {
+            number_ui(ui, "Zoom multiplier", zoom_multiplier)
+                | colour_ui(ui, "Colour", colour)
+                | colour_ui(ui, "Arrow colour", arrow_colour)
+                | number_ui(ui, "Size", size)
+        }
+    }
+}
+
+fn area_style_ui(ui: &mut egui::Ui, style: &mut AreaStyle) -> bool {
+    match style {
+        AreaStyle::Fill {
+            zoom_multiplier,
+            colour,
+            outline,
+            outline_width,
+        } => {
+            ui.strong("Fill");
+            number_ui(ui, "Zoom multiplier", zoom_multiplier)
+                | colour_ui(ui, "Colour", colour)
+                | colour_ui(ui, "Outline", outline)
+                | number_ui(ui, "Outline width", outline_width)
+        }
+        AreaStyle::CenterText {
+            zoom_multiplier,
+            colour,
+            size,
+            ..
+        } => {
+            ui.strong("Centre text");
+            number_ui(ui, "Zoom multiplier", zoom_multiplier)
+                | colour_ui(ui, "Colour", colour)
+                | number_ui(ui, "Size", size)
+        }
+        AreaStyle::BorderText {
+            zoom_multiplier,
+            colour,
+            size,
+            ..
+        } => {
+            ui.strong("Border text");
+            number_ui(ui, "Zoom multiplier", zoom_multiplier)
+                | colour_ui(ui, "Colour", colour)
+                | number_ui(ui, "Size", size)
+        }
+        AreaStyle::CentreImage {
+            zoom_multiplier,
+            size,
+            ..
+        } => {
+            ui.strong("Image");
+            number_ui(ui, "Zoom multiplier", zoom_multiplier)
+                | number_ui(ui, "Width", &mut size.x)
+                | number_ui(ui, "Height", &mut size.y)
+        }
+    }
+}
+
+/// Writes edits from the skin editor to the skin, and drops the draft when the skin is replaced
+pub fn apply_skin_draft_sy(mut skin: ResMut<Skin>, mut skin_draft: ResMut<SkinDraft>) {
+    if skin_draft.is_changed() {
+        if let Some(draft) = &skin_draft.0 {
+            draft.clone_into(&mut *skin);
+        }
+    } else if skin.is_changed() {
+        skin_draft.bypass_change_detection().0 = None;
+    }
+}
+
+#[expect(clippy::needless_pass_by_value)]
+pub fn on_skin_editor(
+    trigger: Trigger<SkinEditorEv>,
+    mut state: ResMut<DockLayout>,
+    mut file_dialogs: ResMut<FileDialogs>,
+    mut skin_draft: ResMut<SkinDraft>,
+    skin: Res<Skin>,
+) {
+    match trigger.event() {
+        SkinEditorEv::Open => {
+            open_dock_window(&mut state, SkinEditor);
+        }
+        SkinEditorEv::Export => {
+            let mut fd = SkinEditor::export_dialog(&skin.name);
+            fd.save_file();
+            file_dialogs.skin_export = Some(fd);
+        }
+        SkinEditorEv::DeleteType(i, name) => {
+            let skin_mut = skin_draft.0.get_or_insert_with(|| (*skin).clone());
+            if skin_mut.types.get(*i).is_some_and(|a| a.name() == name) {
+                skin_mut.types.remove(*i);
+                skin_mut.edited = true;
+            }
+        }
+    }
+}
+
+pub fn skin_editor_dialog(
+    skin: Res<Skin>,
+    mut ctx: EguiContexts,
+    mut file_dialogs: ResMut<FileDialogs>,
+) {
+    let Some(ctx) = ctx.try_ctx_mut() else {
+        return;
+    };
+    if let Some(file_dialog) = &mut file_dialogs.skin_export {
+        file_dialog.update(ctx);
+        if let Some(file) = file_dialog.take_picked() {
+            let _ = FileDialogs::save_storage(file_dialog.storage_mut());
+            if save_json(&*skin, &file, Some("skin")).is_ok() {
+                NOTIF_LOG.push(
+                    format!("Exported skin to {}", file.to_string_lossy()),
+                    ToastLevel::Success,
+                );
+            }
+        }
+    }
+}
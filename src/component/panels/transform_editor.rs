@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::actions::transforming::{Pivot, TransformEv},
+    ui::panel::dock::{open_dock_window, DockLayout, DockWindow, PanelParams},
+};
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct TransformEditor;
+
+#[derive(Clone, Copy, Event)]
+pub struct OpenTransformEditorEv;
+
+impl DockWindow for TransformEditor {
+    fn title(self) -> String {
+        "Transform".into()
+    }
+    fn ui(self, params: &mut PanelParams, ui: &mut egui::Ui) {
+        let PanelParams {
+            queries,
+            commands,
+            transform_settings,
+            ..
+        } = params;
+        let count = queries.p0().iter().count();
+        ui.heading(match count {
+            0 => "Select a component...".into(),
+            1 => "1 component selected".into(),
+            count => format!("{count} components selected"),
+        });
+        ui.separator();
+
+        egui::ComboBox::from_label("Pivot")
+            .selected_text(transform_settings.pivot.to_string())
+            .show_ui(ui, |ui| {
+                for pivot in Pivot::ALL {
+                    ui.selectable_value(&mut transform_settings.pivot, pivot, pivot.to_string());
+                }
+            });
+        if transform_settings.pivot == Pivot::Cursor {
+            ui.label(format!(
+                "x: {} z: {} (M-click the map while transforming to change)",
+                transform_settings.cursor_pivot.x, -transform_settings.cursor_pivot.y
+            ));
+        }
+        ui.add(
+            egui::Slider::new(&mut transform_settings.snap_angle, 0.0..=90.0)
+                .suffix("°")
+                .text("Snap angle"),
+        )
+        .on_hover_text("Used when rotating with the handle. Set to 0 to disable snapping");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut transform_settings.angle)
+                    .speed(1.0)
+                    .suffix("°"),
+            )
+            .on_hover_text("Anticlockwise");
+            if ui
+                .add_enabled(count != 0, egui::Button::new("Rotate"))
+                .clicked()
+            {
+                commands.trigger(TransformEv::Rotate(transform_settings.angle));
+            }
+        });
+        ui.checkbox(&mut transform_settings.uniform, "Uniform scaling");
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut transform_settings.scale.x)
+                    .speed(0.01)
+                    .prefix("×"),
+            );
+            if transform_settings.uniform {
+                transform_settings.scale.y = transform_settings.scale.x;
+            } else {
+                ui.add(
+                    egui::DragValue::new(&mut transform_settings.scale.y)
+                        .speed(0.01)
+                        .prefix("×"),
+                );
+            }
+            if ui
+                .add_enabled(count != 0, egui::Button::new("Scale"))
+                .clicked()
+            {
+                commands.trigger(TransformEv::Scale(transform_settings.scale));
+            }
+        });
+    }
+}
+
+pub fn on_transform_editor(
+    _trigger: Trigger<OpenTransformEditorEv>,
+    mut state: ResMut<DockLayout>,
+) {
+    open_dock_window(&mut state, TransformEditor);
+}
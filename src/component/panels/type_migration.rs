@@ -0,0 +1,163 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+use egui_notify::ToastLevel;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::{actions::rendering::RenderEv, pla2::PlaComponent, skin::Skin},
+    history::{HistoryEntry, HistoryEv},
+    ui::{
+        notif::{NotifLogRwLockExt, NOTIF_LOG},
+        panel::dock::{open_dock_window, DockLayout, DockWindow, PanelParams},
+    },
+};
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct TypeMigration;
+
+#[derive(Clone, Copy, Event)]
+pub struct OpenTypeMigrationEv;
+
+impl DockWindow for TypeMigration {
+    fn title(self) -> String {
+        "Type Migration".into()
+    }
+    fn ui(self, params: &mut PanelParams, ui: &mut egui::Ui) {
+        let PanelParams {
+            queries,
+            commands,
+            skin,
+            type_migrations,
+            ..
+        } = params;
+        let query = queries.p1();
+        let unknown = query
+            .iter()
+            .filter(|(_, a)| skin.get_type(&a.ty).is_none())
+            .map(|(_, a)| (a.ty.clone(), a.guess_type(skin)))
+            .counts();
+        type_migrations.retain(|k, v| unknown.contains_key(k) && skin.get_type(v).is_some());
+
+        if unknown.is_empty() {
+            ui.label("Every loaded component has a type that is in the skin");
+            return;
+        }
+        ui.label(
+            "These types are not in the skin. Map each to another type of the same shape, or keep it as is",
+        );
+        ui.separator();
+
+        egui::Grid::new("type_migration")
+            .striped(true)
+            .num_columns(4)
+            .show(ui, |ui| {
+                ui.strong("Type");
+                ui.strong("Guessed shape");
+                ui.strong("Count");
+                ui.strong("Map to");
+                ui.end_row();
+                for ((ty, shape), count) in unknown.iter().sorted() {
+                    ui.label(ty);
+                    ui.label(format!("{shape:?}"));
+                    ui.label(count.to_string());
+
+                    let key = (ty.to_owned(), *shape);
+                    let mut selected = type_migrations.get(&key).cloned();
+                    egui::ComboBox::from_id_salt(("type_migration", ty, *shape))
+                        .selected_text(selected.as_deref().map_or_else(
+                            || egui::WidgetText::from("Keep"),
+                            |a| skin.show_type(a, ui, &egui::TextStyle::Button).into(),
+                        ))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut selected, None, "Keep");
+                            for skin_type in skin
+                                .types
+                                .iter()
+                                .filter(|a| a.get_type() == *shape)
+                                .sorted_by_key(|a| a.name())
+                            {
+                                let label = skin_type.widget_text(ui, &egui::TextStyle::Button);
+                                ui.selectable_value(
+                                    &mut selected,
+                                    Some(skin_type.name().to_owned()),
+                                    label,
+                                );
+                            }
+                        });
+                    if let Some(selected) = selected {
+                        type_migrations.insert(key, selected);
+                    } else {
+                        type_migrations.remove(&key);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        ui.separator();
+        if ui
+            .add_enabled(!type_migrations.is_empty(), egui::Button::new("Apply"))
+            .clicked()
+        {
+            let histories = query
+                .iter()
+                .filter_map(|(e, pla)| {
+                    let ty = type_migrations.get(&(pla.ty.clone(), pla.guess_type(skin)))?;
+                    let after = PlaComponent {
+                        ty: ty.to_owned(),
+                        ..pla.to_owned()
+                    };
+                    commands
+                        .entity(e)
+                        .insert(after.clone())
+                        .trigger(RenderEv::default());
+                    Some(HistoryEntry::Component {
+                        e,
+                        before: Some(pla.to_owned().into()),
+                        after: Some(after.into()),
+                    })
+                })
+                .collect::<Vec<_>>();
+            NOTIF_LOG.push(
+                format!("Changed the type of {} components", histories.len()),
+                ToastLevel::Success,
+            );
+            commands.trigger(HistoryEv::NewHistory(histories));
+            type_migrations.clear();
+        }
+    }
+}
+
+pub fn on_type_migration(_trigger: Trigger<OpenTypeMigrationEv>, mut state: ResMut<DockLayout>) {
+    open_dock_window(&mut state, TypeMigration);
+}
+
+/// Points the user to the migration wizard whenever a type that is not in the skin shows up
+#[tracing::instrument(skip_all)]
+pub fn notify_unknown_types_sy(
+    skin: Res<Skin>,
+    components: Query<Ref<PlaComponent>>,
+    mut notified: Local<HashSet<String>>,
+) {
+    if !skin.is_changed() && !components.iter().any(|a| a.is_added()) {
+        return;
+    }
+    let unknown = components
+        .iter()
+        .filter(|a| skin.get_type(&a.ty).is_none())
+        .map(|a| a.ty.clone())
+        .collect::<HashSet<_>>();
+    if !unknown.is_subset(&notified) {
+        NOTIF_LOG.push(
+            format!(
+                "{} component type(s) are not in the skin: {}\nUse View > Type Migration to change them",
+                unknown.len(),
+                unknown.iter().sorted().join(", ")
+            ),
+            ToastLevel::Warning,
+        );
+    }
+    *notified = unknown;
+}
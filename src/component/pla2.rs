@@ -8,17 +8,17 @@ use bevy::{
     prelude::*,
 };
 use bevy_prototype_lyon::prelude::*;
-use egui_notify::ToastLevel;
 use hex_color::HexColor;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    component::skin::{Skin, SkinComponent},
-    ui::notif::{NotifLogRwLockExt, NOTIF_LOG},
+    component::skin::{LineStyle, Skin, SkinComponent, SkinImages},
+    ui::map::zoom::SkinZoom,
 };
 
-fn hex_to_color(hex: HexColor) -> Color {
+#[must_use]
+pub fn hex_to_color(hex: HexColor) -> Color {
     Color::srgba(
         f32::from(hex.r) / 255.0,
         f32::from(hex.g) / 255.0,
@@ -72,40 +72,43 @@ impl<T: Coords + PartialEq> PlaComponent<T> {
     pub fn get_skin_type(&self, skin: &Skin) -> ComponentType {
         skin.get_type(self.ty.as_str()).map_or_else(
             || {
-                let (ty, s) = if self.nodes.len() == 1 || self.nodes.iter().dedup().count() == 1 {
-                    (ComponentType::Point, "point")
-                } else if self.nodes.first() == self.nodes.last() && !self.nodes.is_empty() {
-                    (ComponentType::Area, "area")
-                } else {
-                    (ComponentType::Line, "line")
-                };
-                NOTIF_LOG.push(
-                    format!(
-                        "Unknown type {} for component {}\nAssuming it is a(n) {}",
-                        self.ty, self, s
-                    ),
-                    ToastLevel::Warning,
-                );
+                let ty = self.guess_type(skin);
+                warn!(component = %self, "Unknown type {}, assuming it is a(n) {ty:?}", self.ty);
                 ty
             },
             SkinComponent::get_type,
         )
     }
+    /// Guesses the shape of the component for when its type is not in the skin,
+    /// preferring the shape the type had in an earlier skin
     #[must_use]
-    pub fn front_colour<'a>(&self, skin: &'a Skin) -> Option<&'a HexColor> {
-        skin.get_type(self.ty.as_str())?.front_colour()
+    pub fn guess_type(&self, skin: &Skin) -> ComponentType {
+        if let Some(shape) = skin.known_shapes.get(&self.ty) {
+            return *shape;
+        }
+        if self.nodes.len() == 1 || self.nodes.iter().dedup().count() == 1 {
+            ComponentType::Point
+        } else if self.nodes.first() == self.nodes.last() && !self.nodes.is_empty() {
+            ComponentType::Area
+        } else {
+            ComponentType::Line
+        }
     }
     #[must_use]
-    pub fn back_colour<'a>(&self, skin: &'a Skin) -> Option<&'a HexColor> {
-        skin.get_type(self.ty.as_str())?.back_colour()
+    pub fn front_colour<'a>(&self, skin: &'a Skin, zoom: SkinZoom) -> Option<&'a HexColor> {
+        skin.get_type(self.ty.as_str())?.front_colour(zoom)
     }
     #[must_use]
-    pub fn text_colour<'a>(&self, skin: &'a Skin) -> Option<&'a HexColor> {
-        skin.get_type(self.ty.as_str())?.text_colour()
+    pub fn back_colour<'a>(&self, skin: &'a Skin, zoom: SkinZoom) -> Option<&'a HexColor> {
+        skin.get_type(self.ty.as_str())?.back_colour(zoom)
     }
     #[must_use]
-    pub fn weight(&self, skin: &Skin) -> Option<f32> {
-        skin.get_type(self.ty.as_str())?.weight()
+    pub fn text_colour<'a>(&self, skin: &'a Skin, zoom: SkinZoom) -> Option<&'a HexColor> {
+        skin.get_type(self.ty.as_str())?.text_colour(zoom)
+    }
+    #[must_use]
+    pub fn weight(&self, skin: &Skin, zoom: SkinZoom) -> Option<f32> {
+        skin.get_type(self.ty.as_str())?.weight(zoom)
     }
 }
 
@@ -143,7 +146,24 @@ impl PlaComponent {
     }
 
     #[must_use]
-    pub fn get_shape(&self, skin: &Skin) -> (Shape, Transform) {
+    pub fn mc_nodes(&self) -> Vec<MCCoords> {
+        self.nodes.iter().map(|a| (*a).into()).collect()
+    }
+    #[must_use]
+    pub fn length(&self) -> f32 {
+        MCCoords::path_length(&self.mc_nodes(), false)
+    }
+    #[must_use]
+    pub fn perimeter(&self) -> f32 {
+        MCCoords::path_length(&self.mc_nodes(), true)
+    }
+    #[must_use]
+    pub fn area(&self) -> f32 {
+        MCCoords::polygon_area(&self.mc_nodes())
+    }
+
+    #[must_use]
+    pub fn get_shape(&self, skin: &Skin, zoom: SkinZoom) -> (Shape, Transform) {
         if self.get_skin_type(skin) == ComponentType::Point {
             return (
                 ShapeBuilder::with(&shapes::Rectangle {
@@ -151,25 +171,30 @@ impl PlaComponent {
                     origin: RectangleOrigin::Center,
                     ..default()
                 })
-                .fill(self.get_fill(skin))
+                .fill(self.get_fill(skin, zoom))
                 .build(),
                 Transform::from_xyz(self.nodes[0].0.x as f32, self.nodes[0].0.y as f32, 10.0),
             );
         }
-        let shape = ShapeBuilder::with(&{
+        let nodes = self.nodes.iter().map(|a| a.0.as_vec2()).collect::<Vec<_>>();
+        let dash = self
+            .line_layers(skin, zoom)
+            .into_iter()
+            .next()
+            .and_then(|a| a.dash);
+        let shape = ShapeBuilder::with(&if self.get_skin_type(skin) == ComponentType::Area {
             let mut path = ShapePath::new();
-
-            for coord in &self.nodes {
-                path = path.line_to(coord.0.as_vec2());
+            for coord in &nodes {
+                path = path.line_to(*coord);
             }
-            if self.get_skin_type(skin) == ComponentType::Area {
-                if let Some(coord) = self.nodes.first() {
-                    path = path.line_to(coord.0.as_vec2());
-                }
+            if let Some(coord) = nodes.first() {
+                path = path.line_to(*coord);
             }
             path
+        } else {
+            line_path(&nodes, dash.as_deref())
         });
-        let (fill, stroke) = (self.get_fill(skin), self.get_stroke(skin));
+        let (fill, stroke) = (self.get_fill(skin, zoom), self.get_stroke(skin, zoom));
         let shape = match (&fill.color, &stroke.color) {
             (&Color::NONE, &Color::NONE) => shape.fill(Fill::color(Color::NONE)),
             (&Color::NONE, _) => shape.stroke(stroke),
@@ -186,16 +211,16 @@ impl PlaComponent {
     }
 
     #[must_use]
-    pub fn get_fill(&self, skin: &Skin) -> Fill {
+    pub fn get_fill(&self, skin: &Skin, zoom: SkinZoom) -> Fill {
         if self.get_skin_type(skin) == ComponentType::Point {
             return Fill::color(
-                self.front_colour(skin)
+                self.front_colour(skin, zoom)
                     .map_or(Color::WHITE, |hex| hex_to_color(*hex)),
             );
         }
         if self.get_skin_type(skin) == ComponentType::Area {
             Fill::color(
-                self.front_colour(skin)
+                self.front_colour(skin, zoom)
                     .map_or(Color::NONE, |hex| hex_to_color(*hex).with_alpha(0.25)),
             )
         } else {
@@ -203,20 +228,89 @@ impl PlaComponent {
         }
     }
 
+    /// The icon of a point or area centre, with its size and offset in blocks
+    #[must_use]
+    pub fn icon(&self, skin: &Skin, images: &SkinImages, zoom: SkinZoom) -> Option<Icon> {
+        let band_min = skin.get_type(&self.ty)?.band_min(zoom)?;
+        let image = images.0.get(&(self.ty.clone(), band_min))?;
+        let scale = SkinComponent::zoom_scale(image.zoom_multiplier, band_min, zoom)
+            * zoom.blocks_per_pixel();
+        Some(Icon {
+            image: image.image.clone(),
+            size: image.size * scale,
+            offset: Vec2::new(image.offset.x, -image.offset.y) * scale,
+        })
+    }
+
+    /// The `Fore` and `Back` layers of a line type, bottom first. The bottom layer is drawn by the component itself, the rest are drawn as children
+    #[must_use]
+    pub fn line_layers(&self, skin: &Skin, zoom: SkinZoom) -> Vec<LineLayer> {
+        let Some(SkinComponent::Line { styles, .. }) = skin.get_type(&self.ty) else {
+            return Vec::new();
+        };
+        let Some((band_min, styles)) = SkinComponent::style_at_zoom(styles, zoom) else {
+            return Vec::new();
+        };
+        styles
+            .iter()
+            .filter_map(|style| match style {
+                LineStyle::Fore {
+                    zoom_multiplier,
+                    colour: Some(colour),
+                    width,
+                    dash,
+                    unrounded,
+                }
+                | LineStyle::Back {
+                    zoom_multiplier,
+                    colour: Some(colour),
+                    width,
+                    dash,
+                    unrounded,
+                } => {
+                    let scale = SkinComponent::zoom_scale(*zoom_multiplier, band_min, zoom)
+                        * zoom.blocks_per_pixel();
+                    let cap = if *unrounded {
+                        LineCap::Butt
+                    } else {
+                        LineCap::Round
+                    };
+                    Some(LineLayer {
+                        stroke: Stroke {
+                            color: hex_to_color(*colour),
+                            options: StrokeOptions::default()
+                                .with_start_cap(cap)
+                                .with_end_cap(cap)
+                                .with_line_join(LineJoin::Round)
+                                .with_line_width(width * scale),
+                        },
+                        dash: dash
+                            .as_ref()
+                            .map(|dash| dash.iter().map(|a| a * scale).collect()),
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     #[must_use]
-    pub fn get_stroke(&self, skin: &Skin) -> Stroke {
+    pub fn get_stroke(&self, skin: &Skin, zoom: SkinZoom) -> Stroke {
         if self.get_skin_type(skin) == ComponentType::Point {
             return Stroke::color(Color::NONE);
         }
+        if let Some(layer) = self.line_layers(skin, zoom).first() {
+            return layer.stroke;
+        }
         let options = StrokeOptions::default()
             .with_start_cap(LineCap::Round)
             .with_end_cap(LineCap::Round)
             .with_line_join(LineJoin::Round)
-            .with_line_width(self.weight(skin).unwrap_or(2.0));
+            .with_line_width(self.weight(skin, zoom).unwrap_or(2.0) * zoom.blocks_per_pixel());
         if self.get_skin_type(skin) == ComponentType::Area {
             Stroke {
                 color: self
-                    .back_colour(skin)
+                    .back_colour(skin, zoom)
                     .map_or(Color::NONE, |hex| hex_to_color(*hex)),
                 options,
             }
@@ -231,6 +325,62 @@ impl PlaComponent {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct Icon {
+    pub image: Handle<Image>,
+    pub size: Vec2,
+    pub offset: Vec2,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineLayer {
+    pub stroke: Stroke,
+    pub dash: Option<Vec<f32>>,
+}
+
+/// Builds a path through `nodes`, leaving gaps according to an alternating on/off `dash` pattern
+#[must_use]
+pub fn line_path(nodes: &[Vec2], dash: Option<&[f32]>) -> ShapePath {
+    let mut path = ShapePath::new();
+    let Some(dash) = dash.filter(|dash| !dash.is_empty() && dash.iter().all(|a| *a > 0.0)) else {
+        for node in nodes {
+            path = path.line_to(*node);
+        }
+        return path;
+    };
+    let Some(first) = nodes.first() else {
+        return path;
+    };
+    path = path.move_to(*first);
+
+    let mut dashes = dash.iter().copied().cycle();
+    let mut on = true;
+    let mut remaining = dashes.next().unwrap_or_default();
+    for (a, b) in nodes.iter().tuple_windows() {
+        let direction = (*b - *a).normalize_or_zero();
+        let mut pos = *a;
+        let mut left = a.distance(*b);
+        while left > remaining {
+            pos += direction * remaining;
+            left -= remaining;
+            path = if on {
+                path.line_to(pos)
+            } else {
+                path.move_to(pos)
+            };
+            on = !on;
+            remaining = dashes.next().unwrap_or_default();
+        }
+        remaining -= left;
+        path = if on {
+            path.line_to(*b)
+        } else {
+            path.move_to(*b)
+        };
+    }
+    path
+}
+
 pub trait HighlightExt {
     fn select(&mut self, ty: ComponentType) -> &mut Self;
     fn hover(&mut self, ty: ComponentType) -> &mut Self;
@@ -278,6 +428,28 @@ pub struct MCCoords(pub Vec2);
 #[derive(Component, Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EditorCoords(pub IVec2);
 
+impl MCCoords {
+    #[must_use]
+    pub fn path_length(nodes: &[Self], closed: bool) -> f32 {
+        let distance = |(a, b): (&Self, &Self)| a.0.distance(b.0);
+        if closed {
+            nodes.iter().circular_tuple_windows().map(distance).sum()
+        } else {
+            nodes.iter().tuple_windows().map(distance).sum()
+        }
+    }
+    #[must_use]
+    pub fn polygon_area(nodes: &[Self]) -> f32 {
+        (nodes
+            .iter()
+            .circular_tuple_windows()
+            .map(|(a, b): (&Self, &Self)| a.0.perp_dot(b.0))
+            .sum::<f32>()
+            / 2.0)
+            .abs()
+    }
+}
+
 impl From<EditorCoords> for MCCoords {
     fn from(c: EditorCoords) -> Self {
         Self(Vec2::new(c.0.x as f32, -c.0.y as f32))
@@ -300,7 +472,7 @@ impl Coords for MCCoords {}
 
 impl Coords for EditorCoords {}
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub enum ComponentType {
     #[serde(rename = "point")]
     Point,
@@ -0,0 +1,109 @@
+use std::collections::{BTreeMap, HashMap};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::pla2::{ComponentType, PlaComponent},
+    dirs_paths::data_path,
+    file::{load_toml, save_toml},
+};
+
+/// A component without its nodes, used as a template when creating components.
+/// `{n}` in the id, display name or description is replaced with `counter`, which goes up every use
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ComponentPreset {
+    pub shape: ComponentType,
+    #[serde(rename = "type")]
+    pub ty: String,
+    #[serde(default)]
+    pub namespace: String,
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub display_name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub layer: f32,
+    #[serde(default)]
+    pub attributes: HashMap<String, String>,
+    #[serde(default = "default_counter")]
+    pub counter: u32,
+}
+
+const fn default_counter() -> u32 {
+    1
+}
+
+impl ComponentPreset {
+    #[must_use]
+    pub fn from_component(pla: &PlaComponent, shape: ComponentType) -> Self {
+        Self {
+            shape,
+            ty: pla.ty.clone(),
+            namespace: pla.namespace.clone(),
+            id: String::new(),
+            display_name: pla.display_name.clone(),
+            description: pla.description.clone(),
+            tags: pla.tags.clone(),
+            layer: pla.layer,
+            attributes: pla.attributes.clone(),
+            counter: default_counter(),
+        }
+    }
+    /// Makes a new component from the preset, filling in placeholders.
+    /// The id and namespace are left empty if the preset does not set them
+    #[must_use]
+    pub fn instantiate(&self) -> PlaComponent {
+        let fill = |a: &str| a.replace("{n}", &self.counter.to_string());
+        PlaComponent {
+            namespace: self.namespace.clone(),
+            id: fill(&self.id),
+            display_name: fill(&self.display_name),
+            description: fill(&self.description),
+            tags: self.tags.clone(),
+            layer: self.layer,
+            ty: self.ty.clone(),
+            nodes: Vec::new(),
+            attributes: self.attributes.clone(),
+        }
+    }
+}
+
+/// Marks a component being created from a preset, whose counter goes up once it is committed
+#[derive(Component, Clone, Debug)]
+pub struct FromPreset(pub String);
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ComponentPresets(pub BTreeMap<String, ComponentPreset>);
+
+impl ComponentPresets {
+    pub fn load() -> Self {
+        if !data_path("presets.toml").exists() {
+            return Self::default();
+        }
+        match load_toml(&data_path("presets.toml"), Some("presets")) {
+            Ok(str) => {
+                info!("Found presets file");
+                Self(str)
+            }
+            Err(e) => {
+                info!("Couldn't open or parse presets file: {e:?}");
+
+                Self::default()
+            }
+        }
+    }
+    pub fn save(&self) -> eyre::Result<()> {
+        save_toml(&self.0, &data_path("presets.toml"), Some("presets"))
+    }
+    /// Moves the preset on to the next `{n}`, after a component made from it is committed
+    pub fn bump(&mut self, name: &str) {
+        if let Some(preset) = self.0.get_mut(name) {
+            preset.counter += 1;
+        }
+    }
+}
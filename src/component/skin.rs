@@ -7,11 +7,11 @@ use bevy_egui::egui;
 use hex_color::HexColor;
 use serde::{Deserialize, Serialize};
 
-use crate::component::pla2::ComponentType;
+use crate::{component::pla2::ComponentType, ui::map::zoom::SkinZoom};
 
 base64_serde_type!(Base64Standard, STANDARD);
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(tag = "ty")]
 pub enum AreaStyle {
     #[serde(rename = "areaFill")]
@@ -45,7 +45,7 @@ pub enum AreaStyle {
         offset: Vec2,
     },
 }
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(tag = "ty")]
 pub enum LineStyle {
     #[serde(rename = "lineFore")]
@@ -73,7 +73,7 @@ pub enum LineStyle {
         offset: f32,
     },
 }
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(tag = "ty")]
 pub enum PointStyle {
     #[serde(rename = "pointImage")]
@@ -103,7 +103,7 @@ pub enum PointStyle {
     },
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(tag = "shape")]
 pub enum SkinComponent {
     #[serde(rename = "point")]
@@ -141,39 +141,74 @@ impl SkinComponent {
         }
     }
     #[must_use]
+    pub const fn name_mut(&mut self) -> &mut String {
+        match self {
+            Self::Point { name, .. } | Self::Line { name, .. } | Self::Area { name, .. } => name,
+        }
+    }
+    #[must_use]
     pub const fn tags(&self) -> &Vec<String> {
         match self {
             Self::Point { tags, .. } | Self::Line { tags, .. } | Self::Area { tags, .. } => tags,
         }
     }
-    fn style_in_max_zoom<T>(style: &HashMap<String, Vec<T>>) -> Option<&Vec<T>> {
-        Some(
-            style
-                .iter()
-                .map(|(zl, v)| (zl.split('-').next().unwrap().parse::<u8>().unwrap(), v))
-                .find(|(min, _)| *min == 0)?
-                .1,
-        )
+    /// Finds the styles for the zoom band containing `zoom`, or the nearest band if none does.
+    /// Returns the band's minimum zoom along with the styles
+    #[must_use]
+    pub fn style_at_zoom<T>(
+        style: &HashMap<String, Vec<T>>,
+        zoom: SkinZoom,
+    ) -> Option<(u8, &Vec<T>)> {
+        style
+            .iter()
+            .filter_map(|(zl, v)| {
+                let (min, max) = zl.split_once('-')?;
+                Some((min.parse::<u8>().ok()?, max.parse::<u8>().ok()?, v))
+            })
+            .min_by_key(|(min, max, _)| {
+                if zoom.0 < *min {
+                    min - zoom.0
+                } else {
+                    zoom.0.saturating_sub(*max)
+                }
+            })
+            .map(|(min, _, v)| (min, v))
+    }
+    #[must_use]
+    pub fn band_min(&self, zoom: SkinZoom) -> Option<u8> {
+        match self {
+            Self::Point { styles, .. } => Self::style_at_zoom(styles, zoom).map(|a| a.0),
+            Self::Line { styles, .. } => Self::style_at_zoom(styles, zoom).map(|a| a.0),
+            Self::Area { styles, .. } => Self::style_at_zoom(styles, zoom).map(|a| a.0),
+        }
+    }
+    /// Scales a width or size from the skin by `zoom_multiplier` for every level beyond the start of its zoom band
+    #[must_use]
+    pub fn zoom_scale(zoom_multiplier: f32, band_min: u8, zoom: SkinZoom) -> f32 {
+        zoom_multiplier.powi(-i32::from(zoom.0.saturating_sub(band_min)))
     }
 
     #[must_use]
-    pub fn front_colour(&self) -> Option<&HexColor> {
+    pub fn front_colour(&self, zoom: SkinZoom) -> Option<&HexColor> {
         match self {
-            Self::Point { styles, .. } => Self::style_in_max_zoom(styles)?
+            Self::Point { styles, .. } => Self::style_at_zoom(styles, zoom)?
+                .1
                 .iter()
                 .filter_map(|style| match style {
                     PointStyle::Square { colour, .. } => colour.into(),
                     _ => None,
                 })
                 .next_back(),
-            Self::Line { styles, .. } => Self::style_in_max_zoom(styles)?
+            Self::Line { styles, .. } => Self::style_at_zoom(styles, zoom)?
+                .1
                 .iter()
                 .filter_map(|style| match style {
                     LineStyle::Fore { colour, .. } => colour.into(),
                     _ => None,
                 })
                 .next_back(),
-            Self::Area { styles, .. } => Self::style_in_max_zoom(styles)?
+            Self::Area { styles, .. } => Self::style_at_zoom(styles, zoom)?
+                .1
                 .iter()
                 .filter_map(|style| match style {
                     AreaStyle::Fill { colour, .. } => colour.into(),
@@ -183,17 +218,19 @@ impl SkinComponent {
         }
     }
     #[must_use]
-    pub fn back_colour(&self) -> Option<&HexColor> {
+    pub fn back_colour(&self, zoom: SkinZoom) -> Option<&HexColor> {
         match self {
             Self::Point { .. } => None,
-            Self::Line { styles, .. } => Self::style_in_max_zoom(styles)?
+            Self::Line { styles, .. } => Self::style_at_zoom(styles, zoom)?
+                .1
                 .iter()
                 .filter_map(|style| match style {
                     LineStyle::Back { colour, .. } => colour.into(),
                     _ => None,
                 })
                 .next_back(),
-            Self::Area { styles, .. } => Self::style_in_max_zoom(styles)?
+            Self::Area { styles, .. } => Self::style_at_zoom(styles, zoom)?
+                .1
                 .iter()
                 .filter_map(|style| match style {
                     AreaStyle::Fill { outline, .. } => outline.into(),
@@ -203,23 +240,26 @@ impl SkinComponent {
         }
     }
     #[must_use]
-    pub fn text_colour(&self) -> Option<&HexColor> {
+    pub fn text_colour(&self, zoom: SkinZoom) -> Option<&HexColor> {
         match self {
-            Self::Point { styles, .. } => Self::style_in_max_zoom(styles)?
+            Self::Point { styles, .. } => Self::style_at_zoom(styles, zoom)?
+                .1
                 .iter()
                 .filter_map(|style| match style {
                     PointStyle::Text { colour, .. } => colour.into(),
                     _ => None,
                 })
                 .next_back(),
-            Self::Line { styles, .. } => Self::style_in_max_zoom(styles)?
+            Self::Line { styles, .. } => Self::style_at_zoom(styles, zoom)?
+                .1
                 .iter()
                 .filter_map(|style| match style {
                     LineStyle::Text { colour, .. } => colour.into(),
                     _ => None,
                 })
                 .next_back(),
-            Self::Area { styles, .. } => Self::style_in_max_zoom(styles)?
+            Self::Area { styles, .. } => Self::style_at_zoom(styles, zoom)?
+                .1
                 .iter()
                 .filter_map(|style| match style {
                     AreaStyle::CenterText { colour, .. } => colour.into(),
@@ -229,23 +269,41 @@ impl SkinComponent {
         }
     }
     #[must_use]
-    pub fn weight(&self) -> Option<f32> {
+    pub fn weight(&self, zoom: SkinZoom) -> Option<f32> {
         match self {
             Self::Point { .. } => None,
-            Self::Line { styles, .. } => Self::style_in_max_zoom(styles)?
-                .iter()
-                .filter_map(|style| match style {
-                    LineStyle::Fore { width, .. } => Some(*width),
-                    _ => None,
-                })
-                .next_back(),
-            Self::Area { styles, .. } => Self::style_in_max_zoom(styles)?
-                .iter()
-                .filter_map(|style| match style {
-                    AreaStyle::Fill { outline_width, .. } => Some(outline_width * 5.0),
-                    _ => None,
-                })
-                .next_back(),
+            Self::Line { styles, .. } => {
+                let (band_min, styles) = Self::style_at_zoom(styles, zoom)?;
+                styles
+                    .iter()
+                    .filter_map(|style| match style {
+                        LineStyle::Fore {
+                            width,
+                            zoom_multiplier,
+                            ..
+                        } => Some(width * Self::zoom_scale(*zoom_multiplier, band_min, zoom)),
+                        _ => None,
+                    })
+                    .next_back()
+            }
+            Self::Area { styles, .. } => {
+                let (band_min, styles) = Self::style_at_zoom(styles, zoom)?;
+                styles
+                    .iter()
+                    .filter_map(|style| match style {
+                        AreaStyle::Fill {
+                            outline_width,
+                            zoom_multiplier,
+                            ..
+                        } => Some(
+                            outline_width
+                                * 5.0
+                                * Self::zoom_scale(*zoom_multiplier, band_min, zoom),
+                        ),
+                        _ => None,
+                    })
+                    .next_back()
+            }
         }
     }
 
@@ -257,7 +315,7 @@ impl SkinComponent {
     ) -> impl Into<egui::WidgetText> {
         let font_id = &ui.style().text_styles[text_style];
         let mut label = egui::text::LayoutJob::default();
-        let space = if let Some(c) = self.front_colour() {
+        let space = if let Some(c) = self.front_colour(SkinZoom::default()) {
             label.append(
                 "◼",
                 0.0,
@@ -268,7 +326,7 @@ impl SkinComponent {
                 },
             );
             font_id.size / 4.0
-        } else if let Some(c) = self.back_colour() {
+        } else if let Some(c) = self.back_colour(SkinZoom::default()) {
             label.append(
                 "□",
                 0.0,
@@ -294,7 +352,19 @@ impl SkinComponent {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, Resource)]
+#[derive(Clone, Debug)]
+pub struct SkinImage {
+    pub image: Handle<Image>,
+    pub size: Vec2,
+    pub offset: Vec2,
+    pub zoom_multiplier: f32,
+}
+
+/// Decoded `pointImage` and `areaCentreImage` styles, keyed by type name and the start of their zoom band
+#[derive(Resource, Default, Clone)]
+pub struct SkinImages(pub HashMap<(String, u8), SkinImage>);
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Resource)]
 pub struct Skin {
     pub version: u8,
     pub name: String,
@@ -304,6 +374,12 @@ pub struct Skin {
     pub background: HexColor,
     pub prune_small_text: Option<f32>,
     pub licence: String,
+    /// Shapes of the types in this and earlier skins, for components whose type has since been removed
+    #[serde(skip)]
+    pub known_shapes: HashMap<String, ComponentType>,
+    /// Whether the skin has been changed in the skin editor since it was loaded
+    #[serde(skip)]
+    pub edited: bool,
 }
 
 impl Skin {
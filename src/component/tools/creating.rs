@@ -1,23 +1,179 @@
+use std::collections::HashSet;
+
 use bevy::prelude::*;
-use rand::distr::{Alphanumeric, SampleString};
+use eyre::eyre;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     component::{
         actions::{rendering::RenderEv, selecting::SelectEv},
         make_component,
-        pla2::{ComponentType, PlaComponent},
+        pla2::{ComponentType, EditorCoords, MCCoords, PlaComponent},
+        presets::{ComponentPresets, FromPreset},
         skin::Skin,
     },
+    dirs_paths::data_path,
+    file::{load_toml, save_toml},
     history::{HistoryEntry, HistoryEv},
-    project::Namespaces,
+    project::{manifest::ProjectManifest, Namespaces},
     state::EditorState,
     ui::{
         cursor::{mouse_events::Click2, mouse_pos::MousePosWorld},
-        map::window::PointerWithinTilemap,
+        map::{window::PointerWithinTilemap, zoom::SkinZoom},
         panel::status::Status,
     },
 };
 
+const MAX_RECENT_TYPES: usize = 10;
+
+/// The type, tags and layer that new components are created with, or the preset they are created from
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct CreationDefaults {
+    pub point: Option<String>,
+    pub line: Option<String>,
+    pub area: Option<String>,
+    pub recents: Vec<String>,
+    pub favourites: Vec<String>,
+    pub tags: Vec<String>,
+    pub layer: f32,
+    pub preset: Option<String>,
+}
+
+impl CreationDefaults {
+    pub fn load() -> Self {
+        if !data_path("creation_defaults.toml").exists() {
+            return Self::default();
+        }
+        match load_toml(
+            &data_path("creation_defaults.toml"),
+            Some("creation defaults"),
+        ) {
+            Ok(str) => {
+                info!("Found creation defaults file");
+                str
+            }
+            Err(e) => {
+                info!("Couldn't open or parse creation defaults file: {e:?}");
+
+                Self::default()
+            }
+        }
+    }
+    pub fn save(&self) -> eyre::Result<()> {
+        save_toml(
+            self,
+            &data_path("creation_defaults.toml"),
+            Some("creation defaults"),
+        )
+    }
+    /// The chosen type for the shape, falling back to the `simple*` types if it is not in the skin
+    #[must_use]
+    pub fn type_name(&self, ty: ComponentType, skin: &Skin) -> String {
+        match ty {
+            ComponentType::Point => &self.point,
+            ComponentType::Line => &self.line,
+            ComponentType::Area => &self.area,
+        }
+        .as_ref()
+        .filter(|a| skin.get_type(a).is_some_and(|a| a.get_type() == ty))
+        .map_or_else(|| PlaComponent::<EditorCoords>::new(ty).ty, Clone::clone)
+    }
+    pub fn choose(&mut self, ty: ComponentType, name: String) {
+        self.recents.retain(|a| *a != name);
+        self.recents.insert(0, name.clone());
+        self.recents.truncate(MAX_RECENT_TYPES);
+        match ty {
+            ComponentType::Point => self.point = Some(name),
+            ComponentType::Line => self.line = Some(name),
+            ComponentType::Area => self.area = Some(name),
+        }
+    }
+    pub fn toggle_favourite(&mut self, name: &str) {
+        if self.favourites.iter().any(|a| a == name) {
+            self.favourites.retain(|a| a != name);
+        } else {
+            self.favourites.push(name.to_owned());
+        }
+    }
+    /// The chosen preset, if there is one for the shape
+    #[must_use]
+    pub fn preset_for(&self, ty: ComponentType, presets: &ComponentPresets) -> Option<FromPreset> {
+        self.preset
+            .as_ref()
+            .filter(|a| presets.0.get(*a).is_some_and(|a| a.shape == ty))
+            .map(|a| FromPreset(a.to_owned()))
+    }
+    /// Makes a component with no nodes. The id and namespace may be empty,
+    /// and are filled in by the caller
+    #[must_use]
+    pub fn new_component(
+        &self,
+        ty: ComponentType,
+        skin: &Skin,
+        presets: &ComponentPresets,
+    ) -> PlaComponent {
+        if let Some(preset) = self
+            .preset
+            .as_ref()
+            .and_then(|a| presets.0.get(a))
+            .filter(|a| a.shape == ty)
+        {
+            return preset.instantiate();
+        }
+        PlaComponent {
+            ty: self.type_name(ty, skin),
+            tags: self
+                .tags
+                .iter()
+                .filter(|a| !a.is_empty())
+                .cloned()
+                .collect(),
+            layer: self.layer,
+            namespace: String::new(),
+            ..PlaComponent::new(ty)
+        }
+    }
+}
+
+/// Generates an id for the component if it has none, or if its id is already used in its namespace
+fn assign_id<'a, I: Iterator<Item = &'a PlaComponent>>(
+    pla: &mut PlaComponent,
+    components: I,
+    manifest: &ProjectManifest,
+) {
+    let taken = components
+        .filter(|a| a.namespace == pla.namespace)
+        .map(|a| a.id.as_str())
+        .collect::<HashSet<_>>();
+    if pla.id.is_empty() || taken.contains(pla.id.as_str()) {
+        pla.id = manifest.generate_id(pla, &taken);
+    }
+}
+
+/// Puts the component in the previously used namespace, or the default one,
+/// unless it is already in a visible and unlocked one.
+/// Returns false if none of them are visible and unlocked
+fn assign_namespace(pla: &mut PlaComponent, namespaces: &mut Namespaces) -> bool {
+    let usable = |ns: &String| {
+        namespaces.visibilities.get(ns) == Some(&true) && !namespaces.locked.contains(ns)
+    };
+    if usable(&pla.namespace) {
+        return true;
+    }
+    if !usable(&namespaces.prev_used) {
+        if !usable(&namespaces.default_namespace) {
+            return false;
+        }
+        namespaces.prev_used = namespaces.default_namespace.clone();
+    }
+    namespaces.prev_used.clone_into(&mut pla.namespace);
+    true
+}
+
+const NO_NAMESPACE: &str =
+    "Cannot create components, the default namespace is hidden or locked. Show or unlock it first";
+
 const ANGLE_VECTORS: [Vec2; 20] = [
     Vec2::new(4.0, 0.0),
     Vec2::new(4.0, 1.0),
@@ -46,9 +202,6 @@ pub fn on_point_left_click(
     trigger: Trigger<Pointer<Click2>>,
     pickables: Query<(), With<Pickable>>,
     mut commands: Commands,
-    skin: Res<Skin>,
-    mut namespaces: ResMut<Namespaces>,
-    mut status: ResMut<Status>,
     state: Res<State<EditorState>>,
     pointer_within_tilemap: Option<Res<PointerWithinTilemap>>,
 ) {
@@ -59,40 +212,61 @@ pub fn on_point_left_click(
         return;
     }
 
-    let node = trigger
-        .hit
-        .position
-        .unwrap_or_default()
-        .xy()
-        .round()
-        .as_ivec2();
+    commands.trigger(PlaceNodeEv(
+        trigger
+            .hit
+            .position
+            .unwrap_or_default()
+            .xy()
+            .round()
+            .as_ivec2(),
+    ));
+}
+
+#[tracing::instrument(skip_all)]
+pub fn on_place_point(
+    trigger: Trigger<PlaceNodeEv>,
+    mut commands: Commands,
+    skin: Res<Skin>,
+    skin_zoom: Res<SkinZoom>,
+    mut namespaces: ResMut<Namespaces>,
+    mut status: ResMut<Status>,
+    state: Res<State<EditorState>>,
+    creation_defaults: Res<CreationDefaults>,
+    mut presets: ResMut<ComponentPresets>,
+    manifest: Res<ProjectManifest>,
+    components: Query<&PlaComponent>,
+) {
+    if **state != EditorState::CreatingPoint {
+        return;
+    }
+
+    let node = trigger.event().0;
     let pla = {
-        let mut point = PlaComponent::new(ComponentType::Point);
+        let mut point = creation_defaults.new_component(ComponentType::Point, &skin, &presets);
         point.nodes.push(node.into());
-        if !namespaces
-            .visibilities
-            .get(&namespaces.prev_used)
-            .copied()
-            .unwrap_or_default()
-        {
-            namespaces.prev_used = "_misc".into();
+        if !assign_namespace(&mut point, &mut namespaces) {
+            status.set(NO_NAMESPACE);
+            return;
         }
-        namespaces.prev_used.clone_into(&mut point.namespace);
-        point.id = Alphanumeric.sample_string(&mut rand::rng(), 16);
+        assign_id(&mut point, components.iter(), &manifest);
         point
     };
-    let new_point = make_component(pla.clone(), &skin);
+    let new_point = make_component(pla.clone(), &skin, *skin_zoom);
     debug!("Placing new point at {node:?}");
     status.set(format!("Created new point {pla} at {node:?}"));
 
     commands.trigger(SelectEv::DeselectAll);
 
-    let e = commands.spawn(new_point).id();
+    let e = commands.spawn(new_point).trigger(RenderEv::default()).id();
     commands.trigger(HistoryEv::one_history(HistoryEntry::Component {
         e,
         before: None,
         after: Some(pla.into()),
     }));
+    if let Some(FromPreset(preset)) = creation_defaults.preset_for(ComponentType::Point, &presets) {
+        presets.bump(&preset);
+    }
 }
 
 #[tracing::instrument(skip_all)]
@@ -101,18 +275,42 @@ pub fn on_line_area_left_click(
     pickables: Query<(), With<Pickable>>,
     mut commands: Commands,
     state: Res<State<EditorState>>,
-    mut set: CreatedQuery,
-    mut status: ResMut<Status>,
-    skin: Res<Skin>,
     pointer_within_tilemap: Option<Res<PointerWithinTilemap>>,
 ) {
     if pointer_within_tilemap.is_none()
         || trigger.button != PointerButton::Primary
         || trigger.target() != Entity::PLACEHOLDER && !pickables.contains(trigger.target())
+        || ![EditorState::CreatingArea, EditorState::CreatingLine].contains(&state)
     {
         return;
     }
 
+    commands.trigger(PlaceNodeEv(
+        trigger
+            .hit
+            .position
+            .unwrap_or_default()
+            .xy()
+            .round()
+            .as_ivec2(),
+    ));
+}
+
+#[tracing::instrument(skip_all)]
+pub fn on_place_line_area_node(
+    trigger: Trigger<PlaceNodeEv>,
+    mut commands: Commands,
+    state: Res<State<EditorState>>,
+    mut set: CreatedQuery,
+    mut status: ResMut<Status>,
+    skin: Res<Skin>,
+    skin_zoom: Res<SkinZoom>,
+    creation_defaults: Res<CreationDefaults>,
+    presets: Res<ComponentPresets>,
+    mut namespaces: ResMut<Namespaces>,
+    manifest: Res<ProjectManifest>,
+    components: Query<&PlaComponent, Without<CreatedComponent>>,
+) {
     let (ty, ty_text) = match **state {
         EditorState::CreatingArea => (ComponentType::Area, "area"),
         EditorState::CreatingLine => (ComponentType::Line, "line"),
@@ -121,13 +319,7 @@ pub fn on_line_area_left_click(
         }
     };
 
-    let new = trigger
-        .hit
-        .position
-        .unwrap_or_default()
-        .xy()
-        .round()
-        .as_ivec2();
+    let new = trigger.event().0;
     if let Ok((e, mut pla)) = set.single_mut() {
         if pla.nodes.last().map(|a| a.0) == Some(new) {
             pla.nodes.pop();
@@ -151,16 +343,22 @@ pub fn on_line_area_left_click(
         commands.trigger(SelectEv::DeselectAll);
 
         let pla = {
-            let mut pla = PlaComponent::new(ty);
-            pla.id = Alphanumeric.sample_string(&mut rand::rng(), 16);
+            let mut pla = creation_defaults.new_component(ty, &skin, &presets);
+            if !assign_namespace(&mut pla, &mut namespaces) {
+                status.set(NO_NAMESPACE);
+                return;
+            }
+            assign_id(&mut pla, components.iter(), &manifest);
             pla.nodes.push(new.into());
             pla
         };
         debug!("Starting new {ty_text} at {new:?}");
         status.set(format!("Starting new {ty_text} at {new:?}",));
-        commands
-            .spawn(make_component(pla, &skin))
-            .insert(CreatedComponent);
+        let mut new = commands.spawn(make_component(pla, &skin, *skin_zoom));
+        new.insert(CreatedComponent).trigger(RenderEv::default());
+        if let Some(preset) = creation_defaults.preset_for(ty, &presets) {
+            new.insert(preset);
+        }
     }
 }
 
@@ -184,6 +382,42 @@ pub fn on_line_area_right_click(
     commands.trigger(ClearCreatedComponentEv);
 }
 
+#[tracing::instrument(skip_all)]
+pub fn on_coord_input(
+    trigger: Trigger<CoordInputEv>,
+    mut commands: Commands,
+    created_query: Query<&PlaComponent, With<CreatedComponent>>,
+    mut status: ResMut<Status>,
+) {
+    let prev = created_query
+        .single()
+        .ok()
+        .and_then(|pla| pla.nodes.last())
+        .map(|a| a.0);
+    let node = match *trigger.event() {
+        CoordInputEv::Absolute(coord) => EditorCoords::from(MCCoords(coord.round())).0,
+        CoordInputEv::Relative(offset) => {
+            let Some(prev) = prev else {
+                status.set("No component is being created to offset from");
+                return;
+            };
+            prev + EditorCoords::from(MCCoords(offset.round())).0
+        }
+        CoordInputEv::Polar { bearing, distance } => {
+            let Some(prev) = prev else {
+                status.set("No component is being created to offset from");
+                return;
+            };
+            let (sin, cos) = bearing.to_radians().sin_cos();
+            (prev.as_vec2() + Vec2::new(sin, cos) * distance)
+                .round()
+                .as_ivec2()
+        }
+    };
+    debug!(input = ?trigger.event(), ?node, "Placing node from coordinate input");
+    commands.trigger(PlaceNodeEv(node));
+}
+
 #[tracing::instrument(skip_all)]
 pub fn create_component_sy(
     set: Query<(Entity, &PlaComponent), With<CreatedComponent>>,
@@ -217,8 +451,10 @@ pub fn on_clear_created_component(
     _trigger: Trigger<ClearCreatedComponentEv>,
     mut commands: Commands,
     mut created_query: CreatedQuery,
+    from_preset: Query<&FromPreset>,
     skin: Res<Skin>,
     mut namespaces: ResMut<Namespaces>,
+    mut presets: ResMut<ComponentPresets>,
     mut status: ResMut<Status>,
 ) {
     let Ok((e, mut pla)) = created_query.single_mut() else {
@@ -228,25 +464,22 @@ pub fn on_clear_created_component(
     if pla.nodes.len() == 1 {
         commands.entity(e).despawn();
         status.set("Cancelled component creation");
+    } else if !assign_namespace(&mut pla, &mut namespaces) {
+        commands.entity(e).despawn();
+        status.set(NO_NAMESPACE);
     } else {
-        if !namespaces
-            .visibilities
-            .get(&namespaces.prev_used)
-            .copied()
-            .unwrap_or_default()
-        {
-            namespaces.prev_used = "_misc".into();
-        }
-        namespaces.prev_used.clone_into(&mut pla.namespace);
         commands
             .entity(e)
             .trigger(RenderEv::default())
-            .remove::<CreatedComponent>();
+            .remove::<(CreatedComponent, FromPreset)>();
         commands.trigger(HistoryEv::one_history(HistoryEntry::Component {
             e,
             before: None,
             after: Some(pla.to_owned().into()),
         }));
+        if let Ok(FromPreset(preset)) = from_preset.get(e) {
+            presets.bump(preset);
+        }
         status.set(format!(
             "Created new {} {}",
             if pla.get_skin_type(&skin) == ComponentType::Area {
@@ -262,25 +495,90 @@ pub fn on_clear_created_component(
 pub struct CreateComponentPlugin;
 impl Plugin for CreateComponentPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            create_component_sy.run_if(
-                in_state(EditorState::CreatingLine).or(in_state(EditorState::CreatingArea)),
-            ),
-        )
-        .add_observer(on_point_left_click)
-        .add_observer(on_line_area_left_click)
-        .add_observer(on_line_area_right_click)
-        .add_observer(on_clear_created_component);
+        app.insert_resource(CreationDefaults::load())
+            .insert_resource(ComponentPresets::load())
+            .add_systems(
+                Update,
+                (
+                    create_component_sy.run_if(
+                        in_state(EditorState::CreatingLine).or(in_state(EditorState::CreatingArea)),
+                    ),
+                    save_creation_defaults_sy.run_if(
+                        resource_changed::<CreationDefaults>
+                            .and(not(resource_added::<CreationDefaults>)),
+                    ),
+                    save_presets_sy.run_if(
+                        resource_changed::<ComponentPresets>
+                            .and(not(resource_added::<ComponentPresets>)),
+                    ),
+                ),
+            )
+            .add_observer(on_point_left_click)
+            .add_observer(on_place_point)
+            .add_observer(on_line_area_left_click)
+            .add_observer(on_place_line_area_node)
+            .add_observer(on_coord_input)
+            .add_observer(on_line_area_right_click)
+            .add_observer(on_clear_created_component);
     }
 }
 
+#[expect(clippy::needless_pass_by_value)]
+pub fn save_creation_defaults_sy(creation_defaults: Res<CreationDefaults>) {
+    let _ = creation_defaults.save();
+}
+
+#[expect(clippy::needless_pass_by_value)]
+pub fn save_presets_sy(presets: Res<ComponentPresets>) {
+    let _ = presets.save();
+}
+
 pub type CreatedQuery<'world, 'state, 'a> =
     Query<'world, 'state, (Entity, &'a mut PlaComponent), With<CreatedComponent>>;
 
 #[derive(Copy, Clone, Event)]
 pub struct ClearCreatedComponentEv;
 
+#[derive(Copy, Clone, Event)]
+pub struct PlaceNodeEv(pub IVec2);
+
+#[derive(Copy, Clone, Debug, PartialEq, Event)]
+pub enum CoordInputEv {
+    /// `x z`
+    Absolute(Vec2),
+    /// `@dx dz`
+    Relative(Vec2),
+    /// `<bearing distance`, with the bearing in degrees clockwise from north
+    Polar { bearing: f32, distance: f32 },
+}
+
+impl CoordInputEv {
+    pub fn parse(input: &str) -> eyre::Result<Self> {
+        let input = input.trim();
+        let mut chars = input.chars();
+        let (prefix, rest) = match chars.next() {
+            Some(c @ ('@' | '<')) => (Some(c), chars.as_str()),
+            _ => (None, input),
+        };
+        let numbers = rest
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|a| !a.is_empty())
+            .map(|a| a.parse::<f32>().map_err(|_| eyre!("`{a}` is not a number")))
+            .collect::<eyre::Result<Vec<_>>>()?;
+        let [a, b] = *numbers.as_slice() else {
+            return Err(eyre!("Expected 2 numbers, got {}", numbers.len()));
+        };
+        Ok(match prefix {
+            Some('@') => Self::Relative(Vec2::new(a, b)),
+            Some('<') => Self::Polar {
+                bearing: a,
+                distance: b,
+            },
+            _ => Self::Absolute(Vec2::new(a, b)),
+        })
+    }
+}
+
 #[derive(Component)]
 #[component(storage = "SparseSet")]
 pub struct CreatedComponent;
@@ -3,6 +3,7 @@ use bevy::prelude::*;
 use crate::{
     component::pla2::PlaComponent,
     history::{HistoryEntry, HistoryEv},
+    project::Namespaces,
     state::EditorState,
     ui::{cursor::mouse_events::Click2, map::window::PointerWithinTilemap, panel::status::Status},
 };
@@ -29,11 +30,16 @@ pub fn on_delete(
     mut commands: Commands,
     query: Query<&PlaComponent>,
     mut status: ResMut<Status>,
+    namespaces: Res<Namespaces>,
 ) {
     let e = trigger.target();
     let Ok(pla) = query.get(e) else {
         return;
     };
+    if namespaces.locked.contains(&pla.namespace) {
+        status.set(format!("Namespace {} is locked", pla.namespace));
+        return;
+    }
 
     info!(?e, "Deleting entity");
     commands.trigger(HistoryEv::one_history(HistoryEntry::Component {
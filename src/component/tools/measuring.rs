@@ -0,0 +1,134 @@
+use bevy::{color::palettes::basic::AQUA, prelude::*, render::primitives::Aabb};
+use bevy_prototype_lyon::prelude::*;
+
+use crate::{
+    component::pla2::{EditorCoords, MCCoords},
+    state::EditorState,
+    ui::{
+        cursor::{mouse_events::Click2, mouse_pos::MousePosWorld},
+        map::{window::PointerWithinTilemap, zoom::Zoom},
+        panel::status::Status,
+    },
+};
+
+#[derive(Resource, Default, Clone, Debug)]
+pub struct Measurement(pub Vec<EditorCoords>);
+
+#[derive(Component)]
+pub struct MeasurementPath;
+
+#[tracing::instrument(skip_all)]
+pub fn on_measure_click(
+    trigger: Trigger<Pointer<Click2>>,
+    pickables: Query<(), With<Pickable>>,
+    mut measurement: ResMut<Measurement>,
+    state: Res<State<EditorState>>,
+    pointer_within_tilemap: Option<Res<PointerWithinTilemap>>,
+) {
+    if pointer_within_tilemap.is_none()
+        || **state != EditorState::Measuring
+        || trigger.target() != Entity::PLACEHOLDER && !pickables.contains(trigger.target())
+    {
+        return;
+    }
+    match trigger.button {
+        PointerButton::Primary => {
+            let node = trigger
+                .hit
+                .position
+                .unwrap_or_default()
+                .xy()
+                .round()
+                .as_ivec2();
+            debug!(?node, "Adding measurement node");
+            measurement.0.push(node.into());
+        }
+        PointerButton::Secondary => {
+            debug!("Clearing measurement");
+            measurement.0.clear();
+        }
+        PointerButton::Middle => {}
+    }
+}
+
+#[tracing::instrument(skip_all)]
+pub fn measure_sy(
+    mut commands: Commands,
+    measurement: Res<Measurement>,
+    path_query: Query<Entity, With<MeasurementPath>>,
+    mouse_pos_world: Res<MousePosWorld>,
+    zoom: Res<Zoom>,
+    mut status: ResMut<Status>,
+    state: Res<State<EditorState>>,
+    mut last_nodes: Local<Vec<EditorCoords>>,
+) {
+    let mut nodes = measurement.0.clone();
+    if nodes.is_empty() {
+        if state.is_changed() || !last_nodes.is_empty() {
+            status.set("Measuring: L-click to add a node. R-click to clear.");
+            last_nodes.clear();
+        }
+        for e in &path_query {
+            commands.entity(e).despawn();
+        }
+        return;
+    }
+    nodes.push(mouse_pos_world.round().as_ivec2().into());
+    let nodes_changed = state.is_changed() || *last_nodes != nodes;
+    nodes.clone_into(&mut *last_nodes);
+    let mc_nodes = nodes.iter().map(|a| MCCoords::from(*a)).collect::<Vec<_>>();
+
+    let [.., prev, next] = mc_nodes.as_slice() else {
+        return;
+    };
+    if nodes_changed {
+        let segment = prev.0.distance(next.0);
+        let total = MCCoords::path_length(&mc_nodes, false);
+        status.set(if mc_nodes.len() > 2 {
+            format!(
+                "Segment: {segment:.1} blocks | Total: {total:.1} blocks | Area: {:.1} blocks² | Perimeter: {:.1} blocks",
+                MCCoords::polygon_area(&mc_nodes),
+                MCCoords::path_length(&mc_nodes, true)
+            )
+        } else {
+            format!("Segment: {segment:.1} blocks | Total: {total:.1} blocks")
+        });
+    }
+
+    let shape = ShapeBuilder::with(&{
+        let mut path = ShapePath::new();
+        for coord in &nodes {
+            path = path.line_to(coord.0.as_vec2());
+        }
+        path
+    })
+    .fill(Fill::color(AQUA.with_alpha(0.1)))
+    .stroke(Stroke::new(AQUA, 256.0 / zoom.0.exp2()))
+    .build();
+    if let Ok(e) = path_query.single() {
+        commands.entity(e).remove::<Aabb>().insert(shape);
+    } else {
+        commands.spawn((shape, Transform::from_xyz(0.0, 0.0, 90.0), MeasurementPath));
+    }
+}
+
+pub fn clear_measurement_sy(
+    mut commands: Commands,
+    mut measurement: ResMut<Measurement>,
+    path: Query<Entity, With<MeasurementPath>>,
+) {
+    measurement.0.clear();
+    for e in &path {
+        commands.entity(e).despawn();
+    }
+}
+
+pub struct MeasurePlugin;
+impl Plugin for MeasurePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Measurement>()
+            .add_systems(Update, measure_sy.run_if(in_state(EditorState::Measuring)))
+            .add_systems(OnExit(EditorState::Measuring), clear_measurement_sy)
+            .add_observer(on_measure_click);
+    }
+}
@@ -4,6 +4,7 @@ use crate::PluginGroup;
 
 pub mod creating;
 pub mod deleting;
+pub mod measuring;
 pub mod node_editing;
 
 pub struct ComponentToolPlugins;
@@ -13,6 +14,7 @@ impl PluginGroup for ComponentToolPlugins {
         PluginGroupBuilder::start::<Self>()
             .add(creating::CreateComponentPlugin)
             .add(deleting::DeleteComponentPlugin)
+            .add(measuring::MeasurePlugin)
             .add(node_editing::EditNodePlugin)
     }
 }
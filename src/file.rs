@@ -57,6 +57,14 @@ pub fn load_toml<T: DeserializeOwned>(file: &Path, error: Option<&'static str>)
     )
 }
 
+pub fn load_json<T: DeserializeOwned>(file: &Path, error: Option<&'static str>) -> eyre::Result<T> {
+    load_file(
+        file,
+        |file| std::fs::read(file).map(|c| serde_json::from_slice(&c)),
+        error,
+    )
+}
+
 pub fn load_msgpack<T: DeserializeOwned>(
     file: &Path,
     error: Option<&'static str>,
@@ -143,6 +151,14 @@ pub fn save_msgpack<T: Serialize>(
     save_file(o, |o| rmp_serde::to_vec_named(o), file, error)
 }
 
+pub fn save_json<T: Serialize>(
+    o: &T,
+    file: &Path,
+    error: Option<&'static str>,
+) -> eyre::Result<()> {
+    save_file(o, |o| serde_json::to_string_pretty(o), file, error)
+}
+
 pub fn safe_delete(path: &Path, error: Option<&'static str>) -> eyre::Result<PathBuf> {
     let trash_dir = cache_dir("trash");
     let timestamp = SystemTime::now()
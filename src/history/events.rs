@@ -11,9 +11,9 @@ use tracing::debug;
 use crate::{
     component::{actions::rendering::RenderEv, make_component, skin::Skin},
     file::{restore, safe_delete},
-    history::{History, HistoryEntry, HistoryEv, NamespaceAction},
+    history::{ComponentMove, History, HistoryEntry, HistoryEv, NamespaceAction},
     project::{events::ProjectEv, Namespaces},
-    ui::panel::status::Status,
+    ui::{map::zoom::SkinZoom, panel::status::Status},
 };
 
 #[expect(clippy::needless_pass_by_value, clippy::significant_drop_tightening)]
@@ -23,11 +23,13 @@ pub fn on_history(
     mut ids: Local<HashMap<Entity, Arc<RwLock<Entity>>>>,
     mut history: ResMut<History>,
     skin: Res<Skin>,
+    skin_zoom: Res<SkinZoom>,
     mut status: ResMut<Status>,
     mut namespaces: ResMut<Namespaces>,
 ) -> Result {
     match trigger.event() {
-        HistoryEv::NewHistory(histories) => {
+        HistoryEv::NewHistory(histories) | HistoryEv::Coalesce(histories) => {
+            let coalesce = matches!(trigger.event(), HistoryEv::Coalesce(_));
             let histories = histories
                 .iter()
                 .map(|history| match history {
@@ -53,24 +55,45 @@ pub fn on_history(
                     },
                 })
                 .collect::<Vec<_>>();
+            namespaces.dirty.extend(
+                histories
+                    .iter()
+                    .flat_map(HistoryEntry::changed_namespaces)
+                    .map(ToOwned::to_owned),
+            );
             history.redo_stack.clear();
-            if let (
-                Some(
-                    [HistoryEntry::Component {
-                        e: e1, after: a1, ..
-                    }],
-                ),
-                [HistoryEntry::Component {
-                    e: e2, after: a2, ..
-                }],
-            ) = (
-                history.undo_stack.last_mut().map(Vec::as_mut_slice),
-                histories.as_slice(),
-            ) {
-                if *e1.read().map_err(|a| eyre!("{a:?}"))?
-                    == *e2.read().map_err(|a| eyre!("{a:?}"))?
-                {
-                    a2.clone_into(a1);
+            let last_coalesced = std::mem::replace(&mut history.last_coalesced, coalesce);
+            if let Some(last) = history
+                .undo_stack
+                .last_mut()
+                .filter(|_| coalesce && last_coalesced)
+            {
+                let mut same_components = !histories.is_empty() && last.len() == histories.len();
+                for pair in last.iter().zip(&histories) {
+                    if !same_components {
+                        break;
+                    }
+                    same_components = match pair {
+                        (
+                            HistoryEntry::Component { e: e1, .. },
+                            HistoryEntry::Component { e: e2, .. },
+                        ) => {
+                            *e1.read().map_err(|a| eyre!("{a:?}"))?
+                                == *e2.read().map_err(|a| eyre!("{a:?}"))?
+                        }
+                        _ => false,
+                    };
+                }
+                if same_components {
+                    for pair in last.iter_mut().zip(&histories) {
+                        if let (
+                            HistoryEntry::Component { after: a1, .. },
+                            HistoryEntry::Component { after: a2, .. },
+                        ) = pair
+                        {
+                            a2.clone_into(a1);
+                        }
+                    }
                     return Ok(());
                 }
             }
@@ -78,6 +101,7 @@ pub fn on_history(
         }
         HistoryEv::Undo | HistoryEv::Redo => {
             let history = &mut *history;
+            history.last_coalesced = false;
             let (ev, past, stack, other_stack) = if matches!(trigger.event(), HistoryEv::Undo) {
                 (
                     "undo",
@@ -101,6 +125,12 @@ pub fn on_history(
                 "{past} {}",
                 histories.iter().map(ToString::to_string).join("; ")
             ));
+            namespaces.dirty.extend(
+                histories
+                    .iter()
+                    .flat_map(HistoryEntry::changed_namespaces)
+                    .map(ToOwned::to_owned),
+            );
             for history in &mut histories {
                 debug!("{past} {history}");
                 match history {
@@ -110,7 +140,10 @@ pub fn on_history(
                         e: component_id,
                     } => match (trigger.event(), before, after) {
                         (HistoryEv::Undo, Some(pla), None) | (HistoryEv::Redo, None, Some(pla)) => {
-                            let e = commands.spawn(make_component((**pla).clone(), &skin)).id();
+                            let e = commands
+                                .spawn(make_component((**pla).clone(), &skin, *skin_zoom))
+                                .trigger(RenderEv::default())
+                                .id();
                             *component_id.write().map_err(|a| eyre!("{a:?}"))? = e;
                             ids.insert(e, Arc::clone(component_id));
                         }
@@ -159,6 +192,59 @@ pub fn on_history(
                                 }
                                 continue;
                             }
+                            (HistoryEv::Undo, NamespaceAction::Rename(to)) => ProjectEv::Rename {
+                                from: to.to_owned(),
+                                to: namespace.to_owned(),
+                                history_invoked: true,
+                            },
+                            (HistoryEv::Redo, NamespaceAction::Rename(to)) => ProjectEv::Rename {
+                                from: namespace.to_owned(),
+                                to: to.to_owned(),
+                                history_invoked: true,
+                            },
+                            (
+                                HistoryEv::Undo,
+                                NamespaceAction::Merge {
+                                    moves,
+                                    deleted_file,
+                                    ..
+                                },
+                            ) => {
+                                namespaces.visibilities.insert(namespace.to_owned(), true);
+                                if let Some(deleted_file) = deleted_file {
+                                    let _ = restore(
+                                        deleted_file,
+                                        &namespaces.dir.join(format!("{namespace}.pla2.msgpack")),
+                                        Some("namespace file"),
+                                    );
+                                }
+                                ProjectEv::MoveComponents(
+                                    moves.iter().map(ComponentMove::inverse).collect(),
+                                )
+                            }
+                            (
+                                HistoryEv::Redo,
+                                NamespaceAction::Merge {
+                                    moves,
+                                    deleted_file,
+                                    ..
+                                },
+                            ) => {
+                                namespaces.visibilities.remove(namespace);
+                                let path = namespaces.dir.join(format!("{namespace}.pla2.msgpack"));
+                                if path.exists() {
+                                    *deleted_file = safe_delete(&path, Some("namespace file")).ok();
+                                }
+                                ProjectEv::MoveComponents(moves.to_owned())
+                            }
+                            (HistoryEv::Undo, NamespaceAction::Move(moves)) => {
+                                ProjectEv::MoveComponents(
+                                    moves.iter().map(ComponentMove::inverse).collect(),
+                                )
+                            }
+                            (HistoryEv::Redo, NamespaceAction::Move(moves)) => {
+                                ProjectEv::MoveComponents(moves.to_owned())
+                            }
                             (HistoryEv::Undo, NamespaceAction::Delete(deleted_file))
                             | (HistoryEv::Redo, NamespaceAction::Create(deleted_file)) => {
                                 namespaces.visibilities.insert(namespace.to_owned(), false);
@@ -29,6 +29,58 @@ pub enum NamespaceAction {
     Show,
     Create(Option<PathBuf>),
     Delete(Option<PathBuf>),
+    Rename(String),
+    Merge {
+        into: String,
+        moves: Vec<ComponentMove>,
+        deleted_file: Option<PathBuf>,
+    },
+    Move(Vec<ComponentMove>),
+}
+
+/// Moves the component with `id` in `namespace` to `new_namespace`, where it is given `new_id`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComponentMove {
+    pub namespace: String,
+    pub id: String,
+    pub new_namespace: String,
+    pub new_id: String,
+}
+impl ComponentMove {
+    #[must_use]
+    pub fn inverse(&self) -> Self {
+        Self {
+            namespace: self.new_namespace.clone(),
+            id: self.new_id.clone(),
+            new_namespace: self.namespace.clone(),
+            new_id: self.id.clone(),
+        }
+    }
+}
+
+impl<T> HistoryEntry<T> {
+    /// Namespaces whose saved files no longer match the editor after this entry is applied or reverted
+    #[must_use]
+    pub fn changed_namespaces(&self) -> Vec<&str> {
+        match self {
+            Self::Component { before, after, .. } => before
+                .iter()
+                .chain(after)
+                .map(|a| a.namespace.as_str())
+                .collect(),
+            Self::Namespace { namespace, action } => match action {
+                NamespaceAction::Hide | NamespaceAction::Show | NamespaceAction::Delete(_) => {
+                    vec![]
+                }
+                NamespaceAction::Create(_) => vec![namespace.as_str()],
+                NamespaceAction::Rename(to) => vec![namespace.as_str(), to.as_str()],
+                NamespaceAction::Merge { into, .. } => vec![namespace.as_str(), into.as_str()],
+                NamespaceAction::Move(moves) => std::iter::once(namespace.as_str())
+                    .chain(moves.iter().map(|a| a.namespace.as_str()))
+                    .collect(),
+            },
+        }
+    }
 }
 
 impl<T> Display for HistoryEntry<T> {
@@ -59,6 +111,11 @@ impl<T> Display for HistoryEntry<T> {
                 NamespaceAction::Delete(_) => write!(f, "Delete {namespace}"),
                 NamespaceAction::Hide => write!(f, "Hide {namespace}"),
                 NamespaceAction::Show => write!(f, "Show {namespace}"),
+                NamespaceAction::Rename(to) => write!(f, "Rename {namespace} to {to}"),
+                NamespaceAction::Merge { into, .. } => write!(f, "Merge {namespace} into {into}"),
+                NamespaceAction::Move(moves) => {
+                    write!(f, "Move {} components to {namespace}", moves.len())
+                }
             },
         }
     }
@@ -67,6 +124,9 @@ impl<T> Display for HistoryEntry<T> {
 #[derive(Clone, Event)]
 pub enum HistoryEv {
     NewHistory(Vec<HistoryEntry>),
+    /// Merged into the last undo step if that was also coalesced and changed the same components,
+    /// so that repeated small edits like nudges undo together
+    Coalesce(Vec<HistoryEntry>),
     Undo,
     Redo,
 }
@@ -81,6 +141,8 @@ impl HistoryEv {
 pub struct History {
     pub undo_stack: Vec<Vec<HistoryEntry<Arc<RwLock<Entity>>>>>,
     pub redo_stack: Vec<Vec<HistoryEntry<Arc<RwLock<Entity>>>>>,
+    /// Whether the last undo step came from [`HistoryEv::Coalesce`]
+    pub last_coalesced: bool,
 }
 
 pub struct HistoryPlugin;
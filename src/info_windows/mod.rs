@@ -5,6 +5,7 @@ pub mod info;
 pub mod licenses;
 pub mod manual;
 pub mod quit;
+pub mod skin;
 
 #[derive(Clone, Copy, PartialEq, Eq, Event)]
 pub enum InfoWindowsEv {
@@ -13,6 +14,7 @@ pub enum InfoWindowsEv {
     Licenses,
     Manual,
     Quit(bool),
+    Skin,
 }
 
 pub struct InfoWindowsPlugin;
@@ -27,6 +29,7 @@ impl Plugin for InfoWindowsPlugin {
             .add_observer(info::on_info)
             .add_observer(changelog::on_changelog)
             .add_observer(manual::on_manual)
-            .add_observer(licenses::on_license);
+            .add_observer(licenses::on_license)
+            .add_observer(skin::on_skin_info);
     }
 }
@@ -1,8 +1,9 @@
 use bevy::{app::AppExit, prelude::*};
+use itertools::Itertools;
 
 use crate::{
-    component::pla2::PlaComponent,
     info_windows::InfoWindowsEv,
+    project::Namespaces,
     ui::popup::{Popup, Popups},
 };
 
@@ -11,18 +12,21 @@ pub fn on_quit(
     trigger: Trigger<InfoWindowsEv>,
     mut popups: ResMut<Popups>,
     mut exit: EventWriter<AppExit>,
-    components: Query<(), With<PlaComponent>>,
+    namespaces: Res<Namespaces>,
     mut commands: Commands,
 ) {
     match trigger.event() {
         InfoWindowsEv::Quit(false) => {
-            if components.is_empty() || cfg!(debug_assertions) {
+            if !namespaces.has_unsaved() || cfg!(debug_assertions) {
                 commands.trigger(InfoWindowsEv::Quit(true));
             } else {
                 popups.add(Popup::base_confirm(
                     "confirm_quit",
                     "Are you sure you want to exit?",
-                    "You may have unsaved changes",
+                    format!(
+                        "These namespaces have unsaved changes: {}",
+                        namespaces.unsaved().join(", ")
+                    ),
                     InfoWindowsEv::Quit(true),
                 ));
             }
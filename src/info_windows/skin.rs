@@ -0,0 +1,60 @@
+use std::sync::Mutex;
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use crate::{
+    component::skin::Skin,
+    info_windows::InfoWindowsEv,
+    init::load_skin::SkinRefreshEv,
+    misc_config::settings::INIT_MISC_SETTINGS,
+    ui::popup::{Popup, Popups},
+};
+
+#[expect(clippy::needless_pass_by_value)]
+pub fn on_skin_info(trigger: Trigger<InfoWindowsEv>, mut popups: ResMut<Popups>, skin: Res<Skin>) {
+    if *trigger.event() != InfoWindowsEv::Skin {
+        return;
+    }
+    let name = skin.name.clone();
+    let version = skin.version;
+    let licence = skin.licence.clone();
+    let types = skin.types.len();
+    popups.add(Popup::new(
+        "skin_info",
+        || {
+            egui::Window::new("Skin")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        },
+        move |_, ui, commands, shown| {
+            egui::Grid::new("skin_info").num_columns(2).show(ui, |ui| {
+                ui.label("Name");
+                ui.label(name.as_str());
+                ui.end_row();
+                ui.label("Version");
+                ui.label(version.to_string());
+                ui.end_row();
+                ui.label("Licence");
+                ui.label(licence.as_str());
+                ui.end_row();
+                ui.label("Types");
+                ui.label(types.to_string());
+                ui.end_row();
+                ui.label("Source");
+                ui.label(INIT_MISC_SETTINGS.skin_url.as_str());
+                ui.end_row();
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Refresh skin").clicked() {
+                    commands.trigger(SkinRefreshEv::Check { manual: true });
+                }
+                if ui.button("Close").clicked() {
+                    *shown = false;
+                }
+            });
+        },
+        Mutex::new(Box::new(())),
+    ));
+}
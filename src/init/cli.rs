@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use egui_notify::ToastLevel;
+use eyre::{eyre, OptionExt};
+
+use crate::{
+    project::events::ProjectEv,
+    ui::{
+        map::mouse_nav::JumpToEv,
+        notif::{NotifLogRwLockExt, NOTIF_LOG},
+    },
+};
+
+/// Arguments given on the command line, in the form
+/// `stencil2 [project directory] [--namespaces a,b,...] [--at x,z[,zoom]]`
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct CliArgs {
+    pub project: Option<PathBuf>,
+    pub namespaces: Vec<String>,
+    pub at: Option<(Vec2, Option<f32>)>,
+}
+
+impl CliArgs {
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> eyre::Result<Self> {
+        let mut cli = Self::default();
+        while let Some(arg) = args.next() {
+            let (flag, value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with('-') => {
+                    (flag.to_owned(), Some(value.to_owned()))
+                }
+                _ => (arg, None),
+            };
+            let mut value = |name: &str| {
+                value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| eyre!("Missing value for {name}"))
+            };
+            match flag.as_str() {
+                "-n" | "--namespaces" => {
+                    cli.namespaces.extend(
+                        value("--namespaces")?
+                            .split(',')
+                            .filter(|a| !a.is_empty())
+                            .map(ToOwned::to_owned),
+                    );
+                }
+                "--at" => {
+                    let value = value("--at")?;
+                    let mut parts = value.split(',').map(|a| a.trim().parse::<f32>());
+                    let x = parts.next().ok_or_eyre("Missing x in --at")??;
+                    let z = parts.next().ok_or_eyre("Missing z in --at")??;
+                    let zoom = parts.next().transpose()?;
+                    cli.at = Some((Vec2::new(x, z), zoom));
+                }
+                flag if flag.starts_with('-') => return Err(eyre!("Unknown argument {flag}")),
+                _ if cli.project.is_none() => cli.project = Some(flag.into()),
+                _ => return Err(eyre!("Unexpected argument {flag}")),
+            }
+        }
+        Ok(cli)
+    }
+
+    #[must_use]
+    pub fn from_env() -> Self {
+        match Self::parse(std::env::args().skip(1)) {
+            Ok(cli) => {
+                info!(?cli, "Parsed command line arguments");
+                cli
+            }
+            Err(e) => {
+                warn!("Couldn't parse command line arguments: {e:?}");
+                NOTIF_LOG.push(
+                    format!("Couldn't parse command line arguments: {e}"),
+                    ToastLevel::Warning,
+                );
+                Self::default()
+            }
+        }
+    }
+}
+
+#[expect(clippy::needless_pass_by_value)]
+pub fn apply_cli_args_sy(mut commands: Commands, cli: Res<CliArgs>) {
+    if let Some(project) = &cli.project {
+        commands.trigger(ProjectEv::Load(project.to_owned(), false));
+    }
+    for ns in &cli.namespaces {
+        commands.trigger(ProjectEv::Show {
+            ns: ns.to_owned(),
+            history_invoked: true,
+            notif: true,
+        });
+    }
+    if let Some((pos, zoom)) = cli.at {
+        commands.trigger(JumpToEv { pos, zoom });
+    }
+}
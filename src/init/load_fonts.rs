@@ -1,16 +1,25 @@
 use std::{collections::HashMap, sync::Arc};
 
+use base64::{engine::general_purpose::STANDARD, Engine};
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 use egui_notify::ToastLevel;
+use itertools::Itertools;
 
 use crate::{
+    component::{actions::labels::LabelFont, skin::Skin},
     dirs_paths::data_dir,
     state::LoadingState,
     ui::notif::{NotifLogRwLockExt, NOTIF_LOG},
 };
 
-pub fn get_fonts_sy(mut commands: Commands, mut ctx: EguiContexts) -> Result {
+#[tracing::instrument(skip_all)]
+pub fn get_fonts_sy(
+    mut commands: Commands,
+    mut ctx: EguiContexts,
+    skin: Res<Skin>,
+    mut font_assets: ResMut<Assets<Font>>,
+) -> Result {
     info!("Loading fonts");
     let mut fonts = HashMap::new();
     for result in data_dir("fonts").read_dir()? {
@@ -48,6 +57,21 @@ pub fn get_fonts_sy(mut commands: Commands, mut ctx: EguiContexts) -> Result {
         }
     }
 
+    let label_font = skin
+        .font_files
+        .iter()
+        .filter_map(|(_, data)| STANDARD.decode(data).ok())
+        .chain(
+            fonts
+                .iter()
+                .sorted_by_key(|(name, _)| *name)
+                .map(|(_, bytes)| bytes.to_owned()),
+        )
+        .find_map(|bytes| Font::try_from_bytes(bytes).ok());
+    if let Some(font) = label_font {
+        commands.insert_resource(LabelFont(font_assets.add(font)));
+    }
+
     let mut font_definitions = egui::FontDefinitions::default();
     for (name, bytes) in fonts {
         font_definitions
@@ -1,16 +1,29 @@
+use std::{collections::HashMap, path::PathBuf, time::SystemTime};
+
 use async_executor::{Executor, Task};
-use bevy::prelude::*;
+use bevy::{
+    asset::RenderAssetUsages,
+    image::{CompressedImageFormats, ImageSampler, ImageType},
+    prelude::*,
+};
 use egui_notify::ToastLevel;
 use futures_lite::future;
 use tracing::{error, info};
 
 use crate::{
-    component::skin::Skin,
+    component::{
+        pla2::ComponentType,
+        skin::{AreaStyle, PointStyle, Skin, SkinComponent, SkinImage, SkinImages},
+    },
     dirs_paths::cache_path,
-    file::{load_msgpack, save_msgpack},
-    misc_config::settings::INIT_MISC_SETTINGS,
+    file::{load_json, load_msgpack, save_msgpack},
+    misc_config::settings::{MiscSettings, INIT_MISC_SETTINGS},
+    project::manifest::ProjectManifest,
     state::LoadingState,
-    ui::notif::{NotifLogRwLockExt, NOTIF_LOG},
+    ui::{
+        notif::{NotifLogRwLockExt, NOTIF_LOG},
+        popup::{Popup, Popups},
+    },
 };
 
 #[derive(Default)]
@@ -21,16 +34,68 @@ pub enum Step<T> {
     Complete,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SkinSource {
+    Url(String),
+    File(PathBuf),
+}
+
+impl SkinSource {
+    #[must_use]
+    pub fn parse(source: &str) -> Self {
+        if let Some(path) = source.strip_prefix("file://") {
+            Self::File(path.into())
+        } else if source.starts_with("http://") || source.starts_with("https://") {
+            Self::Url(source.into())
+        } else {
+            Self::File(source.into())
+        }
+    }
+}
+
+#[must_use]
+pub fn fallback_skin() -> Skin {
+    serde_json::from_slice(include_bytes!("../../assets/fallback.skin.json"))
+        .expect("Bundled fallback skin is invalid")
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct SkinWatcher {
+    pub path: PathBuf,
+    pub modified: Option<SystemTime>,
+}
+
+impl SkinWatcher {
+    #[must_use]
+    pub fn new(path: PathBuf) -> Self {
+        let modified = path.metadata().and_then(|a| a.modified()).ok();
+        Self { path, modified }
+    }
+}
+
 #[expect(clippy::cognitive_complexity)]
 pub fn get_skin_sy(
     mut commands: Commands,
     mut task_s: Local<Step<surf::Result<Skin>>>,
     mut executor: Local<Option<Executor>>,
 ) {
+    if let SkinSource::File(path) = SkinSource::parse(&INIT_MISC_SETTINGS.skin_url) {
+        if let Ok(skin) = load_json::<Skin>(&path, Some("skin")) {
+            info!(?path, "Loaded skin from file");
+            commands.insert_resource(skin);
+        } else {
+            NOTIF_LOG.push("Using fallback skin", ToastLevel::Warning);
+            commands.insert_resource(fallback_skin());
+        }
+        commands.insert_resource(SkinWatcher::new(path));
+        commands.insert_resource(NextState::Pending(LoadingState::LoadSkin.next()));
+        return;
+    }
     if cache_path("skin.msgpack").exists() {
         if let Ok(skin) = load_msgpack::<Skin>(&cache_path("skin.msgpack"), Some("skin")) {
             info!("Retrieved from cache");
             commands.insert_resource(skin);
+            commands.trigger(SkinRefreshEv::Check { manual: false });
             commands.insert_resource(NextState::Pending(LoadingState::LoadSkin.next()));
             *task_s = Step::Complete;
         }
@@ -60,10 +125,248 @@ pub fn get_skin_sy(
             }
             Some(Err(err)) => {
                 error!(?err, "Unable to retrieve skin");
-                NOTIF_LOG.push(format!("Couldn't download skin.\nMake sure you are connected to the internet.\nError: {err}"), ToastLevel::Error);
+                NOTIF_LOG.push(format!("Couldn't download skin, using fallback skin.\nMake sure you are connected to the internet.\nError: {err}"), ToastLevel::Error);
+                commands.insert_resource(fallback_skin());
+                commands.insert_resource(NextState::Pending(LoadingState::LoadSkin.next()));
                 *task_s = Step::Complete;
             }
         },
         Step::Complete => {}
     }
 }
+
+#[derive(Clone, Copy, PartialEq, Eq, Event)]
+pub enum SkinRefreshEv {
+    Check { manual: bool },
+    Apply,
+}
+
+#[derive(Resource, Default)]
+pub struct SkinRefresh {
+    pub requested: Option<bool>,
+    pub downloaded: Option<Skin>,
+}
+
+fn apply_skin(commands: &mut Commands, skin: Skin) {
+    info!(name = skin.name, version = skin.version, "Updated skin");
+    NOTIF_LOG.push(
+        format!("Updated skin to {} v{}", skin.name, skin.version),
+        ToastLevel::Success,
+    );
+    let _ = save_msgpack(&skin, &cache_path("skin.msgpack"), Some("skin"));
+    commands.insert_resource(skin);
+}
+
+#[expect(clippy::needless_pass_by_value)]
+pub fn on_skin_refresh(
+    trigger: Trigger<SkinRefreshEv>,
+    mut commands: Commands,
+    mut refresh: ResMut<SkinRefresh>,
+) {
+    match trigger.event() {
+        SkinRefreshEv::Check { manual } => refresh.requested = Some(*manual),
+        SkinRefreshEv::Apply => {
+            if let Some(skin) = refresh.downloaded.take() {
+                apply_skin(&mut commands, skin);
+            }
+        }
+    }
+}
+
+fn confirm_skin_update(popups: &mut Popups, refresh: &mut SkinRefresh, skin: &Skin, new: Skin) {
+    popups.add(Popup::base_confirm(
+        "skin_update",
+        "Skin update available",
+        format!(
+            "The skin has changed from {} v{} to {} v{}. Update now?{}",
+            skin.name,
+            skin.version,
+            new.name,
+            new.version,
+            if skin.edited {
+                "\nChanges made in the skin editor will be lost"
+            } else {
+                ""
+            }
+        ),
+        SkinRefreshEv::Apply,
+    ));
+    refresh.downloaded = Some(new);
+}
+
+/// Compares the skin at the skin URL, or the project's, against the loaded one, updating it if asked to by the user
+#[expect(clippy::cognitive_complexity, clippy::needless_pass_by_value)]
+pub fn refresh_skin_sy(
+    mut commands: Commands,
+    mut refresh: ResMut<SkinRefresh>,
+    mut popups: ResMut<Popups>,
+    skin: Res<Skin>,
+    misc_settings: Res<MiscSettings>,
+    manifest: Res<ProjectManifest>,
+    mut task_s: Local<Option<(bool, Task<surf::Result<Skin>>)>>,
+    mut executor: Local<Option<Executor>>,
+) {
+    let executor = executor.get_or_insert_with(Executor::new);
+    let Some((manual, task)) = &mut *task_s else {
+        let Some(manual) = refresh.requested.take() else {
+            return;
+        };
+        match SkinSource::parse(manifest.skin_url(&misc_settings)) {
+            SkinSource::File(path) => match load_json::<Skin>(&path, Some("skin")) {
+                Ok(new) if skin.edited => {
+                    confirm_skin_update(&mut popups, &mut refresh, &skin, new);
+                }
+                Ok(new) => {
+                    info!(?path, "Reloaded skin");
+                    NOTIF_LOG.push("Reloaded skin", ToastLevel::Success);
+                    commands.insert_resource(new);
+                }
+                Err(_) => {}
+            },
+            SkinSource::Url(url) => {
+                info!(manual, "Checking for skin updates");
+                let new_task = executor.spawn(async move {
+                    surf::get(url)
+                        .middleware(surf::middleware::Redirect::default())
+                        .recv_json::<Skin>()
+                        .await
+                });
+                *task_s = Some((manual, new_task));
+            }
+        }
+        return;
+    };
+    let manual = *manual;
+    let Some(result) = future::block_on(future::poll_once(task)) else {
+        executor.try_tick();
+        return;
+    };
+    *task_s = None;
+    match result {
+        Ok(remote) if remote.name == skin.name && remote.version == skin.version => {
+            info!("Skin is up to date");
+            if manual {
+                NOTIF_LOG.push("Skin is up to date", ToastLevel::Info);
+            }
+        }
+        Ok(remote) if manual && !skin.edited => apply_skin(&mut commands, remote),
+        Ok(remote) => {
+            info!(version = remote.version, "Skin update available");
+            confirm_skin_update(&mut popups, &mut refresh, &skin, remote);
+        }
+        Err(err) => {
+            error!(?err, "Unable to check for skin updates");
+            if manual {
+                NOTIF_LOG.push(
+                    format!("Couldn't download skin\nError: {err}"),
+                    ToastLevel::Error,
+                );
+            }
+        }
+    }
+}
+
+#[tracing::instrument(skip_all)]
+pub fn watch_skin_sy(mut commands: Commands, mut watcher: ResMut<SkinWatcher>) {
+    let modified = watcher.path.metadata().and_then(|a| a.modified()).ok();
+    if modified == watcher.modified {
+        return;
+    }
+    watcher.modified = modified;
+    if let Ok(skin) = load_json::<Skin>(&watcher.path, Some("skin")) {
+        info!(path = ?watcher.path, "Reloaded skin");
+        NOTIF_LOG.push("Reloaded skin", ToastLevel::Success);
+        commands.insert_resource(skin);
+    }
+}
+
+#[tracing::instrument(skip_all)]
+pub fn remember_type_shapes_sy(
+    mut skin: ResMut<Skin>,
+    mut known: Local<HashMap<String, ComponentType>>,
+) {
+    known.extend(
+        skin.types
+            .iter()
+            .map(|a| (a.name().to_owned(), a.get_type())),
+    );
+    if skin.known_shapes != *known {
+        skin.bypass_change_detection()
+            .known_shapes
+            .clone_from(&known);
+    }
+}
+
+#[tracing::instrument(skip_all)]
+pub fn load_skin_images_sy(
+    mut commands: Commands,
+    skin: Res<Skin>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let mut skin_images = SkinImages::default();
+    for ty in &skin.types {
+        let styles = match ty {
+            SkinComponent::Point { styles, .. } => styles
+                .iter()
+                .flat_map(|(zl, styles)| {
+                    styles.iter().filter_map(move |style| match style {
+                        PointStyle::Image {
+                            zoom_multiplier,
+                            image,
+                            extension,
+                            size,
+                            offset,
+                        } => Some((zl, *zoom_multiplier, image, extension, *size, *offset)),
+                        _ => None,
+                    })
+                })
+                .collect::<Vec<_>>(),
+            SkinComponent::Area { styles, .. } => styles
+                .iter()
+                .flat_map(|(zl, styles)| {
+                    styles.iter().filter_map(move |style| match style {
+                        AreaStyle::CentreImage {
+                            zoom_multiplier,
+                            image,
+                            extension,
+                            size,
+                            offset,
+                        } => Some((zl, *zoom_multiplier, image, extension, *size, *offset)),
+                        _ => None,
+                    })
+                })
+                .collect::<Vec<_>>(),
+            SkinComponent::Line { .. } => continue,
+        };
+        for (zl, zoom_multiplier, image, extension, size, offset) in styles {
+            let Some(band_min) = zl.split('-').next().and_then(|a| a.parse::<u8>().ok()) else {
+                continue;
+            };
+            let image = match Image::from_buffer(
+                image,
+                ImageType::Extension(extension),
+                CompressedImageFormats::NONE,
+                true,
+                ImageSampler::Default,
+                RenderAssetUsages::default(),
+            ) {
+                Ok(image) => image,
+                Err(e) => {
+                    warn!(?e, "Unable to decode image for {}", ty.name());
+                    continue;
+                }
+            };
+            skin_images.0.insert(
+                (ty.name().to_owned(), band_min),
+                SkinImage {
+                    image: images.add(image),
+                    size,
+                    offset,
+                    zoom_multiplier,
+                },
+            );
+        }
+    }
+    info!(count = skin_images.0.len(), "Decoded skin images");
+    commands.insert_resource(skin_images);
+}
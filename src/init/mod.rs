@@ -1,3 +1,4 @@
+pub mod cli;
 pub mod compat;
 pub mod load_assets;
 pub mod load_fonts;
@@ -7,16 +8,22 @@ pub mod spawn_camera;
 pub mod unzip_assets;
 pub mod welcome;
 
-use bevy::prelude::*;
-use load_skin::get_skin_sy;
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use cli::{apply_cli_args_sy, CliArgs};
+use load_skin::{
+    get_skin_sy, load_skin_images_sy, on_skin_refresh, refresh_skin_sy, remember_type_shapes_sy,
+    watch_skin_sy, SkinRefresh, SkinWatcher,
+};
 
 use crate::{
-    component::skin::Skin,
+    component::skin::{Skin, SkinImages},
     dirs_paths::cache_path,
     file::safe_delete,
     init::load_fonts::get_fonts_sy,
     panic::ack_panic_sy,
-    state::{on_state_change, EditorState, LoadingState},
+    state::{on_state_change, EditorState, IntoSystemConfigExt, LoadingState},
     ui::{map::settings::INIT_TILE_SETTINGS, panel::status::Status},
 };
 
@@ -27,6 +34,7 @@ impl Plugin for InitPlugin {
         app.init_state::<EditorState>()
             .init_state::<LoadingState>()
             .init_resource::<Skin>()
+            .insert_resource(CliArgs::from_env())
             .add_observer(on_state_change)
             .add_systems(OnExit(EditorState::Loading), ack_panic_sy);
         app.add_systems(OnEnter(LoadingState::SetIcon), set_icon::set_icon_sy)
@@ -36,7 +44,20 @@ impl Plugin for InitPlugin {
             )
             .add_plugins(load_assets::LoadAssetsPlugin)
             .add_systems(OnEnter(LoadingState::Compat), compat::compat_sy)
+            .init_resource::<SkinImages>()
             .add_systems(Update, get_skin_sy.run_if(in_state(LoadingState::LoadSkin)))
+            .add_systems(
+                Update,
+                (remember_type_shapes_sy, load_skin_images_sy).run_if(resource_changed::<Skin>),
+            )
+            .add_systems(
+                Update,
+                watch_skin_sy
+                    .run_if(resource_exists::<SkinWatcher>.and(on_timer(Duration::from_secs(1)))),
+            )
+            .init_resource::<SkinRefresh>()
+            .add_observer(on_skin_refresh)
+            .add_systems(Update, refresh_skin_sy.run_if_not_loading())
             .add_systems(
                 Update,
                 get_fonts_sy.run_if(in_state(LoadingState::LoadFonts)),
@@ -46,7 +67,7 @@ impl Plugin for InitPlugin {
                 spawn_camera::spawn_camera_sy,
             )
             .add_systems(OnEnter(LoadingState::Welcome), welcome::welcome_sy)
-            .add_systems(OnEnter(LoadingState::Done), done_sy);
+            .add_systems(OnEnter(LoadingState::Done), (done_sy, apply_cli_args_sy));
     }
 }
 
@@ -2,11 +2,22 @@ use bevy::prelude::*;
 
 use crate::{
     dirs_paths::data_path,
+    init::cli::CliArgs,
+    project::{
+        events::ProjectEv,
+        recent::{launcher_popup, RecentProjects},
+    },
     state::LoadingState,
     ui::popup::{Popup, Popups},
 };
 
-pub fn welcome_sy(mut commands: Commands, mut popups: ResMut<Popups>) {
+#[expect(clippy::needless_pass_by_value)]
+pub fn welcome_sy(
+    mut commands: Commands,
+    mut popups: ResMut<Popups>,
+    recent: Res<RecentProjects>,
+    cli: Res<CliArgs>,
+) {
     if !data_path(".welcome_shown").exists() {
         popups.add(Popup::base_alert(
             "welcome",
@@ -19,6 +30,14 @@ pub fn welcome_sy(mut commands: Commands, mut popups: ResMut<Popups>) {
         ));
         let _ = std::fs::write(data_path(".welcome_shown"), "");
     }
+    if cli.project.is_some() {
+        info!("Project given on the command line, skipping launcher");
+    } else if let Some(last) = recent.last().filter(|_| recent.reopen_last) {
+        info!(dir = ?last.dir, "Reopening last project");
+        commands.trigger(ProjectEv::Load(last.dir.clone(), false));
+    } else if !recent.projects.is_empty() {
+        popups.add(launcher_popup(&recent));
+    }
 
     commands.insert_resource(NextState::Pending(LoadingState::Welcome.next()));
 }
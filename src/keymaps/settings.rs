@@ -9,6 +9,7 @@ use tracing::info;
 use crate::{
     component::panels::{
         component_editor::OpenComponentEditorEv, component_list::OpenComponentListEv,
+        skin_editor::SkinEditorEv, transform_editor::OpenTransformEditorEv,
     },
     dirs_paths::data_path,
     file::{load_toml, save_toml_with_header},
@@ -45,8 +46,16 @@ pub enum KeymapAction {
     ComponentEditor,
     Project,
     ComponentList,
+    Transform,
+    SkinEditor,
     History,
     NotifLog,
+    NudgeUp,
+    NudgeDown,
+    NudgeLeft,
+    NudgeRight,
+    /// Held with a nudge key to move 10 blocks instead of 1
+    NudgeLarge,
 }
 
 impl KeymapAction {
@@ -67,8 +76,16 @@ impl KeymapAction {
             Self::ComponentEditor => commands.trigger(OpenComponentEditorEv),
             Self::Project => commands.trigger(OpenProjectEditorEv),
             Self::ComponentList => commands.trigger(OpenComponentListEv),
+            Self::Transform => commands.trigger(OpenTransformEditorEv),
+            Self::SkinEditor => commands.trigger(SkinEditorEv::Open),
             Self::History => commands.trigger(OpenHistoryViewerEv),
             Self::NotifLog => commands.trigger(OpenNotifLogViewerEv),
+            // Read by `nudge_sy` when the key is pressed, since the large step needs a held key
+            Self::NudgeUp
+            | Self::NudgeDown
+            | Self::NudgeLeft
+            | Self::NudgeRight
+            | Self::NudgeLarge => {}
         }
     }
 }
@@ -104,6 +121,14 @@ impl Default for KeymapSettings {
                     KeymapAction::ChangeState(EditorState::CreatingArea),
                     KeyCode::Digit6,
                 ),
+                (
+                    KeymapAction::ChangeState(EditorState::Measuring),
+                    KeyCode::Digit7,
+                ),
+                (
+                    KeymapAction::ChangeState(EditorState::Transforming),
+                    KeyCode::Digit8,
+                ),
                 (KeymapAction::Undo, KeyCode::KeyU),
                 (KeymapAction::Redo, KeyCode::KeyY),
                 (KeymapAction::Quit, KeyCode::Escape),
@@ -118,8 +143,15 @@ impl Default for KeymapSettings {
                 (KeymapAction::ComponentEditor, KeyCode::KeyC),
                 (KeymapAction::Project, KeyCode::KeyP),
                 (KeymapAction::ComponentList, KeyCode::KeyL),
+                (KeymapAction::Transform, KeyCode::KeyX),
+                (KeymapAction::SkinEditor, KeyCode::KeyE),
                 (KeymapAction::History, KeyCode::KeyH),
                 (KeymapAction::NotifLog, KeyCode::KeyN),
+                (KeymapAction::NudgeUp, KeyCode::ArrowUp),
+                (KeymapAction::NudgeDown, KeyCode::ArrowDown),
+                (KeymapAction::NudgeLeft, KeyCode::ArrowLeft),
+                (KeymapAction::NudgeRight, KeyCode::ArrowRight),
+                (KeymapAction::NudgeLarge, KeyCode::ShiftLeft),
             ]
             .into_iter()
             .collect(),
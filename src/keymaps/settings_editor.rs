@@ -78,7 +78,7 @@ pub fn on_keymap_settings(_trigger: Trigger<OpenKeymapSettingsEv>, mut state: Re
     open_dock_window(&mut state, KeymapSettingsEditor);
 }
 
-pub static KEYMAP_MENU: LazyLock<[(&str, Vec<(KeymapAction, &str)>); 5]> = LazyLock::new(|| {
+pub static KEYMAP_MENU: LazyLock<[(&str, Vec<(KeymapAction, &str)>); 6]> = LazyLock::new(|| {
     [
         (
             "State",
@@ -98,6 +98,11 @@ pub static KEYMAP_MENU: LazyLock<[(&str, Vec<(KeymapAction, &str)>); 5]> = LazyL
                 ),
                 (KeymapAction::ChangeState(EditorState::CreatingLine), "Line"),
                 (KeymapAction::ChangeState(EditorState::CreatingArea), "Area"),
+                (KeymapAction::ChangeState(EditorState::Measuring), "Measure"),
+                (
+                    KeymapAction::ChangeState(EditorState::Transforming),
+                    "Transform",
+                ),
             ]
             .into_iter()
             .collect(),
@@ -130,12 +135,26 @@ pub static KEYMAP_MENU: LazyLock<[(&str, Vec<(KeymapAction, &str)>); 5]> = LazyL
                 (KeymapAction::ComponentEditor, "Component Editor"),
                 (KeymapAction::Project, "Project"),
                 (KeymapAction::ComponentList, "Component List"),
+                (KeymapAction::Transform, "Transform"),
+                (KeymapAction::SkinEditor, "Skin Editor"),
                 (KeymapAction::History, "History"),
                 (KeymapAction::NotifLog, "Notification Log"),
             ]
             .into_iter()
             .collect(),
         ),
+        (
+            "Nudge",
+            [
+                (KeymapAction::NudgeUp, "Up"),
+                (KeymapAction::NudgeDown, "Down"),
+                (KeymapAction::NudgeLeft, "Left"),
+                (KeymapAction::NudgeRight, "Right"),
+                (KeymapAction::NudgeLarge, "Hold for 10 blocks"),
+            ]
+            .into_iter()
+            .collect(),
+        ),
         (
             "Other",
             [
@@ -34,6 +34,8 @@ use crate::{
     keymaps::KeymapPlugin,
     misc_config::MiscSettingsPlugin,
     project::ProjectPlugin,
+    rpc::RpcPlugin,
+    scripting::ScriptingPlugin,
     ui::{notif::NotifPlugin, UiPlugin},
     update_checker::UpdateCheckerPlugin,
     window::{settings::INIT_WINDOW_SETTINGS, WindowSettingsPlugin},
@@ -51,6 +53,8 @@ pub mod keymaps;
 pub mod misc_config;
 pub mod panic;
 pub mod project;
+pub mod rpc;
+pub mod scripting;
 pub mod state;
 pub mod tile;
 pub mod ui;
@@ -151,6 +155,8 @@ fn main() {
         .add_plugins(NotifPlugin)
         .add_plugins(MiscSettingsPlugin)
         .add_plugins(UpdateCheckerPlugin)
+        .add_plugins(RpcPlugin)
+        .add_plugins(ScriptingPlugin)
         .add_plugins(ComponentPanelsPlugin);
 
     #[cfg(debug_assertions)]
@@ -100,6 +100,20 @@ field!(
     autosave_interval,
     u64
 );
+field!(
+    MiscSettings,
+    show_labels_is_default,
+    default_show_labels,
+    show_labels,
+    bool
+);
+field!(
+    MiscSettings,
+    rpc_port_is_default,
+    default_rpc_port,
+    rpc_port,
+    u16
+);
 field!(
     MiscSettings,
     notif_duration_is_default,
@@ -170,6 +184,16 @@ pub struct MiscSettings {
         skip_serializing_if = "notif_duration_is_default"
     )]
     pub notif_duration: u64,
+    #[serde(
+        default = "default_show_labels",
+        skip_serializing_if = "show_labels_is_default"
+    )]
+    pub show_labels: bool,
+    #[serde(
+        default = "default_rpc_port",
+        skip_serializing_if = "rpc_port_is_default"
+    )]
+    pub rpc_port: u16,
 }
 
 impl Default for MiscSettings {
@@ -187,6 +211,8 @@ impl Default for MiscSettings {
             additional_zoom: 3,
             autosave_interval: 60,
             notif_duration: 2,
+            show_labels: true,
+            rpc_port: 0,
         }
     }
 }
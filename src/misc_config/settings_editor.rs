@@ -6,6 +6,7 @@ use surf::Url;
 use crate::{
     dirs_paths::{cache_path, data_path},
     file::safe_delete,
+    init::load_skin::SkinSource,
     misc_config::settings::MiscSettings,
     ui::{
         map::mouse_nav::ScrollMode,
@@ -45,12 +46,24 @@ impl DockWindow for MiscSettingsEditor {
         );
         ui.separator();
 
-        ui.add(egui::TextEdit::singleline(&mut misc_settings.skin_url).hint_text("Skin URL"));
-        if let Err(e) = Url::try_from(&*misc_settings.skin_url) {
-            ui.colored_label(egui::Color32::RED, format!("Invalid URL: {e:?}"));
-            invalid = true;
+        ui.add(
+            egui::TextEdit::singleline(&mut misc_settings.skin_url).hint_text("Skin URL or path"),
+        );
+        match SkinSource::parse(&misc_settings.skin_url) {
+            SkinSource::Url(url) => {
+                if let Err(e) = Url::try_from(&*url) {
+                    ui.colored_label(egui::Color32::RED, format!("Invalid URL: {e:?}"));
+                    invalid = true;
+                }
+            }
+            SkinSource::File(path) => {
+                if !path.is_file() {
+                    ui.colored_label(egui::Color32::RED, "File does not exist");
+                    invalid = true;
+                }
+            }
         }
-        ui.label("The URL for the skin used to render components. Will be cached and retrieved from cache if available. Must be JSON.");
+        ui.label("The URL or file path for the skin used to render components. Must be JSON. Downloaded skins will be cached and retrieved from cache if available. Local skins are reloaded when the file changes.");
         if ui
             .add_enabled(
                 cache_path("skin.msgpack").exists(),
@@ -133,6 +146,16 @@ impl DockWindow for MiscSettingsEditor {
                 .text("Notification duration"),
         );
         ui.label("Time before success and info notifications expire. Set to 0 to disable expiry");
+        ui.separator();
+
+        ui.checkbox(&mut misc_settings.show_labels, "Show labels");
+        ui.label("Shows the display names of components on the map, styled by the skin");
+        ui.separator();
+
+        ui.add(
+            egui::DragValue::new(&mut misc_settings.rpc_port).prefix("Automation server port: "),
+        );
+        ui.label("Port on localhost for the JSON-RPC server used by external tools, which must first send an auth request with the token in rpc_token in the data directory. Set to 0 to disable. Requires a restart");
 
         if !invalid && old_settings != **misc_settings {
             misc_settings.save().unwrap();
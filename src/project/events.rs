@@ -1,21 +1,36 @@
-use std::path::PathBuf;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
 
 use bevy::prelude::*;
 use bevy_egui::EguiContexts;
 use egui_notify::ToastLevel;
+use hex_color::HexColor;
 use itertools::Itertools;
 
 use crate::{
     component::{
+        actions::{rendering::RenderEv, selecting::SelectedComponent},
         make_component,
         pla2::{MCCoords, PlaComponent},
         skin::Skin,
     },
     file::{load_msgpack, safe_delete, save_msgpack},
-    history::{History, HistoryEntry, HistoryEv, NamespaceAction},
-    project::Namespaces,
+    history::{ComponentMove, History, HistoryEntry, HistoryEv, NamespaceAction},
+    init::load_skin::SkinRefreshEv,
+    misc_config::settings::MiscSettings,
+    project::{
+        manifest::{ProjectManifest, UserBasemaps},
+        settings::ProjectSettings,
+        Namespaces,
+    },
     ui::{
         file_dialogs::FileDialogs,
+        map::{
+            settings::{Basemap, TileSettings},
+            zoom::SkinZoom,
+        },
         notif::{NotifLogRwLockExt, NOTIF_LOG},
         popup::{Popup, Popups},
     },
@@ -24,6 +39,8 @@ use crate::{
 #[derive(Clone, PartialEq, Eq, Event)]
 pub enum ProjectEv {
     Open,
+    /// Loads another project, asking whether to save the current one first
+    Switch(PathBuf),
     Load(PathBuf, bool),
     Reload,
     Show {
@@ -38,6 +55,134 @@ pub enum ProjectEv {
     },
     Delete(String),
     Save(bool),
+    Rename {
+        from: String,
+        to: String,
+        history_invoked: bool,
+    },
+    Merge {
+        from: String,
+        into: String,
+    },
+    MoveSelected(String),
+    MoveComponents(Vec<ComponentMove>),
+    Lock {
+        ns: String,
+        locked: bool,
+    },
+    SetColour {
+        ns: String,
+        colour: Option<HexColor>,
+    },
+    SetDefault(String),
+    SaveManifest,
+}
+
+/// Applies the project manifest after the project's namespaces have been found
+fn apply_manifest(
+    manifest: &ProjectManifest,
+    commands: &mut Commands,
+    namespaces: &mut Namespaces,
+    tile_settings: &mut TileSettings,
+    user_basemaps: &mut UserBasemaps,
+) {
+    if let Some(basemap) = &manifest.basemap {
+        user_basemaps
+            .0
+            .get_or_insert_with(|| tile_settings.basemaps.clone());
+        let i = tile_settings
+            .basemaps
+            .iter()
+            .position(|a| a.url == *basemap)
+            .unwrap_or_else(|| {
+                tile_settings.basemaps.insert(
+                    0,
+                    Basemap {
+                        url: basemap.to_owned(),
+                        ..default()
+                    },
+                );
+                0
+            });
+        tile_settings.basemaps.swap(0, i);
+    }
+    for (ns, state) in &manifest.namespaces {
+        match state.locked {
+            Some(true) => {
+                namespaces.locked.insert(ns.to_owned());
+            }
+            Some(false) => {
+                namespaces.locked.remove(ns);
+            }
+            None => {}
+        }
+        if let Some(colour) = state.colour {
+            namespaces.colours.insert(ns.to_owned(), colour);
+        }
+        if state.visible == Some(true) && *ns != namespaces.default_namespace {
+            commands.trigger(ProjectEv::Show {
+                ns: ns.to_owned(),
+                history_invoked: true,
+                notif: false,
+            });
+        }
+    }
+}
+
+/// Shows the default namespace, marking it visible without loading if it has no file yet
+/// so that new components can go into it
+pub fn show_default_namespace(commands: &mut Commands, namespaces: &mut Namespaces) {
+    let ns = namespaces.default_namespace.clone();
+    if namespaces.dir.join(format!("{ns}.pla2.msgpack")).exists() {
+        commands.trigger(ProjectEv::Show {
+            ns,
+            history_invoked: true,
+            notif: false,
+        });
+    } else {
+        namespaces.visibilities.insert(ns, true);
+    }
+}
+
+/// Plans moving `moving` into `into`, giving new ids to components whose id is already taken there
+fn plan_moves(
+    moving: &[&PlaComponent],
+    into: &str,
+    query: &Query<(Entity, &PlaComponent)>,
+    manifest: &ProjectManifest,
+) -> Vec<ComponentMove> {
+    let mut taken = query
+        .iter()
+        .filter(|(_, a)| a.namespace == into)
+        .map(|(_, a)| a.id.clone())
+        .collect::<HashSet<_>>();
+    moving
+        .iter()
+        .map(|pla| {
+            let new_id = if taken.contains(&pla.id) {
+                manifest.generate_id(pla, &taken.iter().map(String::as_str).collect())
+            } else {
+                pla.id.clone()
+            };
+            taken.insert(new_id.clone());
+            ComponentMove {
+                namespace: pla.namespace.clone(),
+                id: pla.id.clone(),
+                new_namespace: into.to_owned(),
+                new_id,
+            }
+        })
+        .collect()
+}
+
+fn rename_namespace_file(from: &Path, to: &Path, namespace: &str) -> eyre::Result<()> {
+    let mut components = load_msgpack::<Vec<PlaComponent<MCCoords>>>(from, Some("pla2"))?;
+    for c in &mut components {
+        namespace.clone_into(&mut c.namespace);
+    }
+    save_msgpack(&components, to, Some("pla2"))?;
+    std::fs::remove_file(from)?;
+    Ok(())
 }
 
 #[expect(clippy::needless_pass_by_value)]
@@ -48,7 +193,14 @@ pub fn on_project(
     query: Query<(Entity, &PlaComponent)>,
     mut file_dialogs: ResMut<FileDialogs>,
     skin: Res<Skin>,
+    skin_zoom: Res<SkinZoom>,
     mut history: ResMut<History>,
+    selected: Query<&PlaComponent, With<SelectedComponent>>,
+    manifest: Res<ProjectManifest>,
+    misc_settings: Res<MiscSettings>,
+    mut tile_settings: ResMut<TileSettings>,
+    mut user_basemaps: ResMut<UserBasemaps>,
+    mut popups: ResMut<Popups>,
 ) {
     match trigger.event() {
         ProjectEv::Show {
@@ -56,6 +208,9 @@ pub fn on_project(
             history_invoked,
             notif,
         } => {
+            if namespaces.visibilities.get(ns) == Some(&true) {
+                return;
+            }
             if !namespaces.dir.join(format!("{ns}.pla2.msgpack")).exists() {
                 return;
             }
@@ -64,8 +219,12 @@ pub fn on_project(
                 &namespaces.dir.join(format!("{ns}.pla2.msgpack")),
                 Some("pla2"),
             ) {
-                for c in components {
-                    commands.spawn(make_component(c.to_editor_coords(), &skin));
+                for mut c in components {
+                    // The file's name is what decides the namespace
+                    ns.clone_into(&mut c.namespace);
+                    commands
+                        .spawn(make_component(c.to_editor_coords(), &skin, *skin_zoom))
+                        .trigger(RenderEv::default());
                 }
                 if !history_invoked {
                     commands.trigger(HistoryEv::one_history(HistoryEntry::Namespace {
@@ -83,6 +242,14 @@ pub fn on_project(
             history_invoked,
             notif,
         } => {
+            // Locked namespaces can't be saved, so hiding one would throw its changes away
+            if namespaces.locked.contains(ns) && namespaces.dirty.contains(ns) {
+                NOTIF_LOG.push(
+                    format!("Namespace {ns} is locked and has unsaved changes, unlock it and save before hiding it"),
+                    ToastLevel::Warning,
+                );
+                return;
+            }
             namespaces.visibilities.insert(ns.to_owned(), false);
             let components = query
                 .iter()
@@ -92,18 +259,20 @@ pub fn on_project(
                 .iter()
                 .map(|(_, p)| p.to_mc_coords())
                 .collect::<Vec<_>>();
-            if save_msgpack(
-                &component_data
-                    .iter()
-                    .sorted_by_key(|a| &a.id)
-                    .collect::<Vec<_>>(),
-                &namespaces.dir.join(format!("{ns}.pla2.msgpack")),
-                Some("pla2"),
-            )
-            .is_err()
+            if !namespaces.locked.contains(ns)
+                && save_msgpack(
+                    &component_data
+                        .iter()
+                        .sorted_by_key(|a| &a.id)
+                        .collect::<Vec<_>>(),
+                    &namespaces.dir.join(format!("{ns}.pla2.msgpack")),
+                    Some("pla2"),
+                )
+                .is_err()
             {
                 return;
             }
+            namespaces.dirty.remove(ns);
             for (e, _) in components {
                 commands.entity(e).despawn();
             }
@@ -114,29 +283,59 @@ pub fn on_project(
                 }));
             }
             if *notif {
-                NOTIF_LOG.push(format!("Saved namespace {ns}"), ToastLevel::Success);
+                NOTIF_LOG.push(
+                    if namespaces.locked.contains(ns) {
+                        format!("Hid locked namespace {ns}")
+                    } else {
+                        format!("Saved namespace {ns}")
+                    },
+                    ToastLevel::Success,
+                );
             }
         }
         ProjectEv::Save(auto) => {
-            let components = query
+            let Namespaces {
+                dirty,
+                visibilities,
+                ..
+            } = &mut *namespaces;
+            dirty.retain(|ns| visibilities.contains_key(ns));
+            let to_save = namespaces
+                .unsaved()
+                .filter(|ns| !namespaces.locked.contains(*ns))
+                .cloned()
+                .collect::<Vec<_>>();
+            if to_save.is_empty() {
+                if !auto {
+                    NOTIF_LOG.push("Nothing to save", ToastLevel::Info);
+                }
+                return;
+            }
+            let mut components = query
                 .iter()
+                .filter(|(_, p)| to_save.contains(&p.namespace))
                 .map(|(_, p)| p.to_mc_coords())
                 .into_group_map_by(|a| a.namespace.clone());
-            for (ns, components) in &components {
-                let _ = save_msgpack(
+            for ns in &to_save {
+                let components = components.remove(ns).unwrap_or_default();
+                if save_msgpack(
                     &components
                         .iter()
                         .sorted_by_key(|a| &a.id)
                         .collect::<Vec<_>>(),
                     &namespaces.dir.join(format!("{ns}.pla2.msgpack")),
                     Some("pla2"),
-                );
+                )
+                .is_ok()
+                {
+                    namespaces.dirty.remove(ns);
+                }
             }
             NOTIF_LOG.push(
                 &if *auto {
-                    format!("Auto-saved {} namespaces", components.len())
+                    format!("Auto-saved {} namespaces", to_save.len())
                 } else {
-                    format!("Saved {} namespaces", components.len())
+                    format!("Saved {} namespaces", to_save.len())
                 },
                 ToastLevel::Success,
             );
@@ -168,7 +367,12 @@ pub fn on_project(
             }
         }
         ProjectEv::Delete(ns) => {
+            if namespaces.locked.contains(ns) {
+                NOTIF_LOG.push(format!("Namespace {ns} is locked"), ToastLevel::Warning);
+                return;
+            }
             namespaces.visibilities.remove(ns);
+            namespaces.dirty.remove(ns);
             let delete_file = namespaces
                 .dir
                 .join(format!("{ns}.pla2.msgpack"))
@@ -186,6 +390,193 @@ pub fn on_project(
                 action: NamespaceAction::Delete(delete_file),
             }));
         }
+        ProjectEv::Rename {
+            from,
+            to,
+            history_invoked,
+        } => {
+            if to.is_empty() || namespaces.visibilities.contains_key(to) {
+                NOTIF_LOG.push(
+                    format!("Cannot rename {from}, namespace {to} already exists"),
+                    ToastLevel::Warning,
+                );
+                return;
+            }
+            let Some(vis) = namespaces.visibilities.remove(from) else {
+                return;
+            };
+            let path = namespaces.dir.join(format!("{from}.pla2.msgpack"));
+            if path.exists() {
+                let new_path = namespaces.dir.join(format!("{to}.pla2.msgpack"));
+                // Components of hidden namespaces are not loaded, so they are moved in the file itself
+                let result = if vis {
+                    std::fs::rename(&path, &new_path).map_err(eyre::Report::from)
+                } else {
+                    rename_namespace_file(&path, &new_path, to)
+                };
+                if let Err(e) = result {
+                    NOTIF_LOG.push(
+                        format!("Could not rename namespace file {}:\n{e}", path.display()),
+                        ToastLevel::Warning,
+                    );
+                    namespaces.visibilities.insert(from.to_owned(), vis);
+                    return;
+                }
+            }
+            namespaces.visibilities.insert(to.to_owned(), vis);
+            if namespaces.locked.remove(from) {
+                namespaces.locked.insert(to.to_owned());
+            }
+            if namespaces.prev_used == *from {
+                to.clone_into(&mut namespaces.prev_used);
+            }
+            commands.trigger(ProjectEv::MoveComponents(
+                query
+                    .iter()
+                    .filter(|(_, a)| a.namespace == *from)
+                    .map(|(_, a)| ComponentMove {
+                        namespace: from.to_owned(),
+                        id: a.id.clone(),
+                        new_namespace: to.to_owned(),
+                        new_id: a.id.clone(),
+                    })
+                    .collect(),
+            ));
+            if !history_invoked {
+                commands.trigger(HistoryEv::one_history(HistoryEntry::Namespace {
+                    namespace: from.to_owned(),
+                    action: NamespaceAction::Rename(to.to_owned()),
+                }));
+            }
+            NOTIF_LOG.push(
+                format!("Renamed namespace {from} to {to}"),
+                ToastLevel::Success,
+            );
+        }
+        ProjectEv::Merge { from, into } => {
+            if from == into
+                || namespaces.visibilities.get(from) != Some(&true)
+                || namespaces.visibilities.get(into) != Some(&true)
+            {
+                NOTIF_LOG.push(
+                    format!("Both {from} and {into} must be visible to merge them"),
+                    ToastLevel::Warning,
+                );
+                return;
+            }
+            if namespaces.locked.contains(from) || namespaces.locked.contains(into) {
+                NOTIF_LOG.push(
+                    format!("Cannot merge {from} into {into}, one of them is locked"),
+                    ToastLevel::Warning,
+                );
+                return;
+            }
+            let moving = query
+                .iter()
+                .filter(|(_, a)| a.namespace == *from)
+                .map(|(_, a)| a)
+                .collect::<Vec<_>>();
+            let moves = plan_moves(&moving, into, &query, &manifest);
+            let renamed = moves.iter().filter(|a| a.id != a.new_id).count();
+
+            namespaces.visibilities.remove(from);
+            if namespaces.prev_used == *from {
+                into.clone_into(&mut namespaces.prev_used);
+            }
+            let path = namespaces.dir.join(format!("{from}.pla2.msgpack"));
+            let deleted_file = path
+                .exists()
+                .then(|| safe_delete(&path, Some("namespace file")).ok())
+                .flatten();
+            commands.trigger(ProjectEv::MoveComponents(moves.clone()));
+            commands.trigger(HistoryEv::one_history(HistoryEntry::Namespace {
+                namespace: from.to_owned(),
+                action: NamespaceAction::Merge {
+                    into: into.to_owned(),
+                    moves,
+                    deleted_file,
+                },
+            }));
+            NOTIF_LOG.push(
+                format!(
+                    "Merged namespace {from} into {into}, {renamed} components were given new ids"
+                ),
+                ToastLevel::Success,
+            );
+        }
+        ProjectEv::MoveSelected(to) => {
+            if namespaces.locked.contains(to) {
+                NOTIF_LOG.push(format!("Namespace {to} is locked"), ToastLevel::Warning);
+                return;
+            }
+            if namespaces.visibilities.get(to) != Some(&true) {
+                NOTIF_LOG.push(
+                    format!("Namespace {to} must be visible to move components into it"),
+                    ToastLevel::Warning,
+                );
+                return;
+            }
+            let moving = selected
+                .iter()
+                .filter(|a| a.namespace != *to)
+                .collect::<Vec<_>>();
+            if moving.is_empty() {
+                return;
+            }
+            let moves = plan_moves(&moving, to, &query, &manifest);
+            commands.trigger(ProjectEv::MoveComponents(moves.clone()));
+            NOTIF_LOG.push(
+                format!("Moved {} components to {to}", moves.len()),
+                ToastLevel::Success,
+            );
+            commands.trigger(HistoryEv::one_history(HistoryEntry::Namespace {
+                namespace: to.to_owned(),
+                action: NamespaceAction::Move(moves),
+            }));
+        }
+        ProjectEv::MoveComponents(moves) => {
+            let moves = moves
+                .iter()
+                .map(|a| ((a.namespace.as_str(), a.id.as_str()), a))
+                .collect::<HashMap<_, _>>();
+            for (e, pla) in &query {
+                let Some(m) = moves.get(&(pla.namespace.as_str(), pla.id.as_str())) else {
+                    continue;
+                };
+                commands
+                    .entity(e)
+                    .insert(PlaComponent {
+                        namespace: m.new_namespace.clone(),
+                        id: m.new_id.clone(),
+                        ..pla.to_owned()
+                    })
+                    .trigger(RenderEv::default());
+            }
+        }
+        ProjectEv::Lock { ns, locked } => {
+            if *locked {
+                namespaces.locked.insert(ns.to_owned());
+                NOTIF_LOG.push(format!("Locked namespace {ns}"), ToastLevel::Info);
+            } else {
+                namespaces.locked.remove(ns);
+                NOTIF_LOG.push(format!("Unlocked namespace {ns}"), ToastLevel::Info);
+            }
+        }
+        ProjectEv::Switch(dir) => {
+            if namespaces.dir == Namespaces::default().dir {
+                commands.trigger(ProjectEv::Load(dir.to_owned(), true));
+            } else if !namespaces.has_unsaved() {
+                commands.trigger(ProjectEv::Load(dir.to_owned(), false));
+            } else {
+                popups.add(Popup::base_choose(
+                    "save-before-switching",
+                    "Save before switching projects?",
+                    format!("Unsaved namespaces: {}", namespaces.unsaved().join(", ")),
+                    ProjectEv::Load(dir.to_owned(), true),
+                    ProjectEv::Load(dir.to_owned(), false),
+                ));
+            }
+        }
         ProjectEv::Load(dir, true) => {
             commands.trigger(ProjectEv::Save(false));
             commands.trigger(ProjectEv::Load(dir.to_owned(), false));
@@ -195,37 +586,84 @@ pub fn on_project(
             history.undo_stack.clear();
             dir.clone_into(&mut namespaces.dir);
             namespaces.visibilities.clear();
+            namespaces.dirty.clear();
+            let project_settings = ProjectSettings::load(dir);
+            namespaces
+                .locked
+                .clone_from(&project_settings.locked_namespaces);
+            namespaces.colours.clear();
+            let new_manifest = ProjectManifest::load(dir);
+            namespaces.default_namespace = new_manifest
+                .as_ref()
+                .and_then(|a| a.default_namespace.clone())
+                .unwrap_or_else(|| Namespaces::default().default_namespace);
+            namespaces.prev_used = namespaces.default_namespace.clone();
             for (e, _) in query.iter() {
                 commands.entity(e).despawn();
             }
             commands.trigger(ProjectEv::Reload);
+            show_default_namespace(&mut commands, &mut namespaces);
+            if let Some(basemaps) = user_basemaps.0.take() {
+                tile_settings.basemaps = basemaps;
+            }
+            if let Some(new_manifest) = &new_manifest {
+                apply_manifest(
+                    new_manifest,
+                    &mut commands,
+                    &mut namespaces,
+                    &mut tile_settings,
+                    &mut user_basemaps,
+                );
+                if let Some(name) = &new_manifest.name {
+                    NOTIF_LOG.push(format!("Opened project {name}"), ToastLevel::Success);
+                }
+            }
+            let new_manifest = new_manifest.unwrap_or_default();
+            let skin_changed =
+                new_manifest.skin_url(&misc_settings) != manifest.skin_url(&misc_settings);
+            commands.insert_resource(project_settings);
+            commands.insert_resource(new_manifest);
+            if skin_changed {
+                commands.trigger(SkinRefreshEv::Check { manual: false });
+            }
+        }
+        ProjectEv::SetColour { ns, colour } => match colour {
+            Some(colour) => {
+                namespaces.colours.insert(ns.to_owned(), *colour);
+            }
+            None => {
+                namespaces.colours.remove(ns);
+            }
+        },
+        ProjectEv::SetDefault(ns) => {
+            ns.clone_into(&mut namespaces.default_namespace);
+        }
+        ProjectEv::SaveManifest => {
+            let mut manifest = (*manifest).clone();
+            manifest.default_namespace = Some(namespaces.default_namespace.clone());
+            manifest.basemap = Some(tile_settings.basemap().url.clone());
+            manifest.record_namespaces(&namespaces);
+            if manifest.save(&namespaces.dir).is_ok() {
+                NOTIF_LOG.push(
+                    format!("Saved {}", ProjectManifest::FILE_NAME),
+                    ToastLevel::Success,
+                );
+                commands.insert_resource(manifest);
+            }
         }
     }
 }
 
-#[expect(clippy::needless_pass_by_value)]
 pub fn project_dialog_sy(
     mut commands: Commands,
-    namespaces: Res<Namespaces>,
     mut ctx: EguiContexts,
     mut file_dialogs: ResMut<FileDialogs>,
-    mut popups: ResMut<Popups>,
 ) {
     let file_dialog = &mut file_dialogs.project_select;
     let Some(ctx) = ctx.try_ctx_mut() else { return };
     file_dialog.update(ctx);
     if let Some(file) = file_dialog.take_picked() {
         let _ = FileDialogs::save_storage(file_dialog.storage_mut());
-        if namespaces.dir == Namespaces::default().dir {
-            commands.trigger(ProjectEv::Load(file, true));
-        } else {
-            popups.add(Popup::base_choose(
-                "save-before-switching",
-                "Save before switching projects?",
-                "",
-                ProjectEv::Load(file.clone(), true),
-                ProjectEv::Load(file, false),
-            ));
-        }
+        commands.trigger(ProjectEv::Switch(file));
     }
 }
@@ -0,0 +1,136 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use hex_color::HexColor;
+use rand::distr::{Alphanumeric, SampleString};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::pla2::PlaComponent,
+    file::{load_toml, save_toml},
+    misc_config::settings::MiscSettings,
+    project::{
+        settings::{slugify, IdScheme},
+        Namespaces,
+    },
+    ui::map::settings::Basemap,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct NamespaceManifest {
+    pub visible: Option<bool>,
+    pub locked: Option<bool>,
+    pub colour: Option<HexColor>,
+}
+
+/// `stencil.toml` in the project directory, which stores how the project should be set up when opened
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ProjectManifest {
+    pub name: Option<String>,
+    pub default_namespace: Option<String>,
+    /// URL or path of the skin
+    pub skin: Option<String>,
+    /// URL of the basemap
+    pub basemap: Option<String>,
+    pub id_scheme: IdScheme,
+    pub id_prefix: String,
+    pub namespaces: BTreeMap<String, NamespaceManifest>,
+}
+
+impl ProjectManifest {
+    pub const FILE_NAME: &'static str = "stencil.toml";
+
+    #[must_use]
+    pub fn path(dir: &Path) -> PathBuf {
+        dir.join(Self::FILE_NAME)
+    }
+    #[must_use]
+    pub fn load(dir: &Path) -> Option<Self> {
+        let path = Self::path(dir);
+        if !path.exists() {
+            return None;
+        }
+        match load_toml(&path, Some("project manifest")) {
+            Ok(str) => {
+                info!(?dir, "Found project manifest file");
+                Some(str)
+            }
+            Err(e) => {
+                info!("Couldn't open or parse project manifest file: {e:?}");
+                None
+            }
+        }
+    }
+    pub fn save(&self, dir: &Path) -> eyre::Result<()> {
+        save_toml(self, &Self::path(dir), Some("project manifest"))
+    }
+
+    /// Records the current visibility, lock and colour of every namespace
+    pub fn record_namespaces(&mut self, namespaces: &Namespaces) {
+        self.namespaces = namespaces
+            .visibilities
+            .iter()
+            .map(|(ns, vis)| {
+                (
+                    ns.to_owned(),
+                    NamespaceManifest {
+                        visible: Some(*vis),
+                        locked: Some(namespaces.locked.contains(ns)),
+                        colour: namespaces.colours.get(ns).copied(),
+                    },
+                )
+            })
+            .collect();
+    }
+
+    /// The skin to use, which the manifest can override for its project
+    #[must_use]
+    pub fn skin_url<'a>(&'a self, misc_settings: &'a MiscSettings) -> &'a str {
+        self.skin.as_deref().unwrap_or(&misc_settings.skin_url)
+    }
+
+    /// Generates an id for the component according to the id scheme, not in `taken`
+    #[must_use]
+    pub fn generate_id(&self, pla: &PlaComponent, taken: &HashSet<&str>) -> String {
+        let random = || loop {
+            let id = Alphanumeric.sample_string(&mut rand::rng(), 16);
+            if !taken.contains(id.as_str()) {
+                break id;
+            }
+        };
+        match self.id_scheme {
+            IdScheme::Random => random(),
+            IdScheme::Sequential => {
+                let next = taken
+                    .iter()
+                    .filter_map(|a| a.strip_prefix(&self.id_prefix)?.parse::<u64>().ok())
+                    .max()
+                    .map_or(1, |a| a + 1);
+                format!("{}{next}", self.id_prefix)
+            }
+            IdScheme::Slug => {
+                let slug = slugify(&pla.display_name);
+                if slug.is_empty() {
+                    return random();
+                }
+                if !taken.contains(slug.as_str()) {
+                    return slug;
+                }
+                (2..)
+                    .map(|n| format!("{slug}-{n}"))
+                    .find(|a| !taken.contains(a.as_str()))
+                    .unwrap_or_else(random)
+            }
+        }
+    }
+}
+
+/// The user's basemaps from before a project's manifest moved its basemap to the front,
+/// put back when another project is loaded
+#[derive(Resource, Default)]
+pub struct UserBasemaps(pub Option<Vec<Basemap>>);
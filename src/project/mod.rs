@@ -1,35 +1,79 @@
-use std::{collections::HashMap, path::PathBuf, time::Duration};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::PathBuf,
+    time::Duration,
+};
 
-use bevy::prelude::*;
+use bevy::{prelude::*, window::PrimaryWindow};
 use bevy_egui::EguiContextPass;
 use events::ProjectEv;
+use hex_color::HexColor;
 
-use crate::{dirs_paths::cache_dir, misc_config::settings::MiscSettings, state::EditorState};
+use crate::{
+    component::{
+        actions::{
+            rendering::RenderEv,
+            selecting::{SelectEv, SelectedComponent},
+        },
+        pla2::PlaComponent,
+    },
+    dirs_paths::cache_dir,
+    misc_config::settings::MiscSettings,
+    project::{
+        manifest::{ProjectManifest, UserBasemaps},
+        recent::RecentProjects,
+        settings::ProjectSettings,
+    },
+    state::EditorState,
+};
 
 pub mod events;
+pub mod manifest;
 pub mod project_editor;
+pub mod recent;
+pub mod settings;
 
 #[derive(Resource, Clone)]
 pub struct Namespaces {
     pub dir: PathBuf,
     pub visibilities: HashMap<String, bool>,
     pub prev_used: String,
+    /// The namespace new components go into when the previously used one is hidden
+    pub default_namespace: String,
+    pub colours: HashMap<String, HexColor>,
+    /// Namespaces whose components cannot be selected, edited or deleted, and are never saved
+    pub locked: BTreeSet<String>,
+    /// Namespaces that have changed since they were last saved
+    pub dirty: BTreeSet<String>,
 }
 
 impl Default for Namespaces {
     fn default() -> Self {
         Self {
             dir: cache_dir("scratchpad"),
-            visibilities: {
-                let mut h = HashMap::new();
-                h.insert("_misc".into(), true);
-                h
-            },
+            visibilities: HashMap::new(),
             prev_used: "_misc".into(),
+            default_namespace: "_misc".into(),
+            colours: HashMap::new(),
+            locked: BTreeSet::new(),
+            dirty: BTreeSet::new(),
         }
     }
 }
 
+impl Namespaces {
+    /// Visible namespaces with changes that have not been saved
+    pub fn unsaved(&self) -> impl Iterator<Item = &String> {
+        self.dirty
+            .iter()
+            .filter(|ns| self.visibilities.get(*ns) == Some(&true))
+    }
+    #[must_use]
+    pub fn has_unsaved(&self) -> bool {
+        self.unsaved().next().is_some()
+    }
+}
+
 #[expect(clippy::needless_pass_by_value)]
 pub fn autosave_sy(
     mut commands: Commands,
@@ -52,22 +96,101 @@ pub fn autosave_sy(
     }
 }
 
+#[tracing::instrument(skip_all)]
+pub fn sync_locked_namespaces_sy(
+    mut commands: Commands,
+    namespaces: Res<Namespaces>,
+    mut project_settings: ResMut<ProjectSettings>,
+    components: Query<(Entity, &PlaComponent, Has<SelectedComponent>)>,
+) {
+    if project_settings.locked_namespaces == namespaces.locked {
+        return;
+    }
+    let changed = project_settings
+        .locked_namespaces
+        .symmetric_difference(&namespaces.locked)
+        .map(String::as_str)
+        .collect::<HashSet<_>>();
+    for (e, pla, selected) in &components {
+        if !changed.contains(pla.namespace.as_str()) {
+            continue;
+        }
+        if selected && namespaces.locked.contains(&pla.namespace) {
+            commands.trigger_targets(SelectEv::Deselect, e);
+        } else {
+            commands.trigger_targets(RenderEv::default(), e);
+        }
+    }
+    project_settings
+        .locked_namespaces
+        .clone_from(&namespaces.locked);
+    let _ = project_settings.save(&namespaces.dir);
+}
+
+#[tracing::instrument(skip_all)]
+pub fn window_title_sy(
+    namespaces: Res<Namespaces>,
+    manifest: Res<ProjectManifest>,
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = window.single_mut() else {
+        return;
+    };
+    let project = if namespaces.dir == Namespaces::default().dir {
+        "Scratchpad".into()
+    } else {
+        manifest.name.clone().unwrap_or_else(|| {
+            namespaces
+                .dir
+                .file_name()
+                .unwrap_or(namespaces.dir.as_os_str())
+                .to_string_lossy()
+                .into_owned()
+        })
+    };
+    let title = format!(
+        "{project}{} - Stencil",
+        if namespaces.has_unsaved() { "*" } else { "" }
+    );
+    if window.title != title {
+        window.title = title;
+    }
+}
+
 pub struct ProjectPlugin;
 
 impl Plugin for ProjectPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Namespaces>()
-            .add_systems(Update, autosave_sy)
-            .add_observer(events::on_project)
-            .add_observer(project_editor::on_project_editor)
-            .add_systems(EguiContextPass, events::project_dialog_sy)
-            .add_systems(OnExit(EditorState::Loading), |mut commands: Commands| {
+        let project_settings = ProjectSettings::load(&Namespaces::default().dir);
+        app.insert_resource(Namespaces {
+            locked: project_settings.locked_namespaces.clone(),
+            ..default()
+        })
+        .insert_resource(project_settings)
+        .init_resource::<ProjectManifest>()
+        .init_resource::<UserBasemaps>()
+        .insert_resource(RecentProjects::load())
+        .add_observer(recent::on_recent_projects)
+        .add_observer(recent::on_project_opened)
+        .add_systems(Update, autosave_sy)
+        .add_systems(
+            Update,
+            sync_locked_namespaces_sy.run_if(resource_changed::<Namespaces>),
+        )
+        .add_systems(
+            Update,
+            window_title_sy
+                .run_if(resource_changed::<Namespaces>.or(resource_changed::<ProjectManifest>)),
+        )
+        .add_observer(events::on_project)
+        .add_observer(project_editor::on_project_editor)
+        .add_systems(EguiContextPass, events::project_dialog_sy)
+        .add_systems(
+            OnExit(EditorState::Loading),
+            |mut commands: Commands, mut namespaces: ResMut<Namespaces>| {
                 commands.trigger(ProjectEv::Reload);
-                commands.trigger(ProjectEv::Show {
-                    ns: "_misc".into(),
-                    history_invoked: true,
-                    notif: false,
-                });
-            });
+                events::show_default_namespace(&mut commands, &mut namespaces);
+            },
+        );
     }
 }
@@ -1,19 +1,23 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
 use bevy::prelude::*;
 use bevy_egui::egui;
-use egui_extras::{Column, TableBuilder};
 use egui_file_dialog::FileDialog;
+use hex_color::HexColor;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     history::{HistoryEntry, HistoryEv, NamespaceAction},
-    project::events::ProjectEv,
+    project::{events::ProjectEv, manifest::ProjectManifest, settings::IdScheme},
     ui::{
         file_dialogs::FileDialogs,
         panel::dock::{open_dock_window, DockLayout, DockWindow, PanelParams},
     },
 };
 
+pub const NAMESPACE_SEPARATOR: char = '.';
+
 #[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct ProjectEditor;
 
@@ -30,9 +34,13 @@ impl DockWindow for ProjectEditor {
             new_namespace,
             commands,
             queries,
+            project_settings,
+            rename_namespace,
+            manifest,
             ..
         } = params;
         let components = queries.p1().iter().counts_by(|(_, a)| a.namespace.clone());
+        let num_selected = queries.p0().iter().count();
         ui.horizontal(|ui| {
             if ui.button("Open").clicked() {
                 commands.trigger(ProjectEv::Open);
@@ -40,107 +48,390 @@ impl DockWindow for ProjectEditor {
             if ui.button("Reload").clicked() {
                 commands.trigger(ProjectEv::Reload);
             }
-            if ui.button("Save").clicked() {
+            if ui
+                .button(if namespaces.has_unsaved() {
+                    "Save*"
+                } else {
+                    "Save"
+                })
+                .clicked()
+            {
                 commands.trigger(ProjectEv::Save(false));
             }
+            if ui
+                .button("Save manifest")
+                .on_hover_text(format!(
+                    "Save the current setup of the project to {}",
+                    ProjectManifest::FILE_NAME
+                ))
+                .clicked()
+            {
+                commands.trigger(ProjectEv::SaveManifest);
+            }
         });
         ui.label(format!(
             "Project directory: {}",
             namespaces.dir.to_string_lossy()
         ));
-        TableBuilder::new(ui)
-            .striped(true)
-            .column(Column::auto().at_least(0.05))
-            .columns(Column::auto().at_least(25.0), 2)
-            .column(Column::auto().at_least(0.05))
-            .header(20.0, |mut header| {
-                header.col(|ui| {
-                    ui.label("👁").on_hover_text("Visibility");
-                });
-                header.col(|ui| {
-                    ui.label("Namespace");
-                });
-                header.col(|ui| {
-                    ui.label("#");
+        let mut name = manifest.name.clone().unwrap_or_default();
+        if ui
+            .add(egui::TextEdit::singleline(&mut name).hint_text("Project name"))
+            .changed()
+        {
+            manifest.name = (!name.is_empty()).then_some(name);
+        }
+        ui.horizontal(|ui| {
+            ui.label("ID scheme")
+                .on_hover_text("Saved with the manifest");
+            egui::ComboBox::from_id_salt("id_scheme")
+                .selected_text(manifest.id_scheme.name())
+                .show_ui(ui, |ui| {
+                    for scheme in IdScheme::ALL {
+                        ui.selectable_value(&mut manifest.id_scheme, scheme, scheme.name());
+                    }
                 });
-            })
-            .body(|mut body| {
-                let mut delete = None;
-                for (ns, vis) in &mut namespaces.visibilities {
-                    body.row(20.0, |mut row| {
-                        row.col(|ui| {
-                            if ui.checkbox(vis, "").changed() {
-                                if *vis {
-                                    commands.trigger(ProjectEv::Show {
-                                        ns: ns.to_owned(),
-                                        history_invoked: false,
-                                        notif: true,
-                                    });
-                                } else {
-                                    commands.trigger(ProjectEv::Hide {
-                                        ns: ns.to_owned(),
-                                        history_invoked: false,
-                                        notif: true,
-                                    });
-                                }
-                            }
-                        });
-                        row.col(|ui| {
-                            ui.label(egui::RichText::new(ns).code());
-                        });
-                        let num_components = components.get(ns).copied().unwrap_or_default();
-                        row.col(|ui| {
-                            ui.label(if *vis {
-                                num_components.to_string()
-                            } else {
-                                "-".into()
-                            });
-                        });
-                        row.col(|ui| {
-                            if ui
-                                .add_enabled(
-                                    num_components == 0 && ns != "_misc" && !*vis,
-                                    egui::Button::new("❌").fill(egui::Color32::DARK_RED),
-                                )
-                                .clicked()
-                            {
-                                delete = Some(ns.to_owned());
-                            }
-                        });
+            if manifest.id_scheme == IdScheme::Sequential {
+                egui::TextEdit::singleline(&mut manifest.id_prefix)
+                    .hint_text("Prefix")
+                    .desired_width(80.0)
+                    .show(ui);
+            }
+        });
+        let old_settings = (**project_settings).clone();
+        let mut tree_ui = NamespaceTreeUi {
+            visibilities: &namespaces.visibilities,
+            locked: &namespaces.locked,
+            dirty: &namespaces.dirty,
+            colours: &namespaces.colours,
+            default_namespace: &namespaces.default_namespace,
+            components: &components,
+            num_selected,
+            rename_namespace: &mut **rename_namespace,
+            expanded: &mut project_settings.expanded_groups,
+            events: Vec::new(),
+        };
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            tree_ui.children_ui(ui, &NamespaceTree::new(namespaces.visibilities.keys()));
+        });
+        for event in std::mem::take(&mut tree_ui.events) {
+            commands.trigger(event);
+        }
+        if **project_settings != old_settings {
+            let _ = project_settings.save(&namespaces.dir);
+        }
+
+        ui.horizontal(|ui| {
+            egui::TextEdit::singleline(&mut **new_namespace)
+                .hint_text("New namespace")
+                .show(ui);
+            if ui
+                .add_enabled(
+                    !new_namespace.is_empty()
+                        && !namespaces.visibilities.contains_key(&**new_namespace),
+                    egui::Button::new("➕"),
+                )
+                .clicked()
+            {
+                namespaces
+                    .visibilities
+                    .insert(new_namespace.to_owned(), true);
+
+                commands.trigger(HistoryEv::one_history(HistoryEntry::Namespace {
+                    namespace: new_namespace.to_owned(),
+                    action: NamespaceAction::Create(None),
+                }));
+                new_namespace.clear();
+            }
+        });
+    }
+}
+
+/// Namespaces grouped by their name split on [`NAMESPACE_SEPARATOR`]
+#[derive(Default)]
+struct NamespaceTree {
+    path: String,
+    namespace: Option<String>,
+    children: BTreeMap<String, NamespaceTree>,
+}
+
+impl NamespaceTree {
+    fn new<'a, I: Iterator<Item = &'a String>>(namespaces: I) -> Self {
+        let mut root = Self::default();
+        for ns in namespaces {
+            let mut node = &mut root;
+            for part in ns.split(NAMESPACE_SEPARATOR) {
+                let path = if node.path.is_empty() {
+                    part.to_owned()
+                } else {
+                    format!("{}{NAMESPACE_SEPARATOR}{part}", node.path)
+                };
+                node = node
+                    .children
+                    .entry(part.to_owned())
+                    .or_insert_with(|| Self {
+                        path,
+                        ..Self::default()
                     });
+            }
+            node.namespace = Some(ns.to_owned());
+        }
+        root
+    }
+    fn namespaces(&self) -> Vec<&str> {
+        self.namespace
+            .as_deref()
+            .into_iter()
+            .chain(self.children.values().flat_map(Self::namespaces))
+            .collect()
+    }
+}
+
+struct NamespaceTreeUi<'a> {
+    visibilities: &'a HashMap<String, bool>,
+    locked: &'a BTreeSet<String>,
+    dirty: &'a BTreeSet<String>,
+    colours: &'a HashMap<String, HexColor>,
+    default_namespace: &'a str,
+    components: &'a HashMap<String, usize>,
+    num_selected: usize,
+    rename_namespace: &'a mut String,
+    expanded: &'a mut BTreeSet<String>,
+    events: Vec<ProjectEv>,
+}
+
+impl NamespaceTreeUi<'_> {
+    fn children_ui(&mut self, ui: &mut egui::Ui, tree: &NamespaceTree) {
+        for (name, child) in &tree.children {
+            if child.children.is_empty() {
+                if let Some(ns) = &child.namespace {
+                    self.namespace_ui(ui, name, ns);
                 }
-                if let Some(delete) = delete {
-                    commands.trigger(ProjectEv::Delete(delete));
+            } else {
+                self.group_ui(ui, name, child);
+            }
+        }
+    }
+
+    fn group_ui(&mut self, ui: &mut egui::Ui, name: &str, tree: &NamespaceTree) {
+        let members = tree.namespaces();
+        let num_shown = members
+            .iter()
+            .filter(|ns| self.visibilities.get(**ns).copied().unwrap_or_default())
+            .count();
+        let num_components = members
+            .iter()
+            .filter_map(|ns| self.components.get(*ns))
+            .sum::<usize>();
+
+        let id = ui.make_persistent_id(("namespace_group", &tree.path));
+        let mut state =
+            egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, false);
+        state.set_open(self.expanded.contains(&tree.path));
+        let (toggle, _, _) = state
+            .show_header(ui, |ui| {
+                let mut all_shown = num_shown == members.len();
+                if ui.checkbox(&mut all_shown, "").changed() {
+                    for ns in &members {
+                        if self.visibilities.get(*ns).copied().unwrap_or_default() != all_shown {
+                            self.events.push(visibility_ev(ns, all_shown));
+                        }
+                    }
                 }
-                body.row(20.0, |mut row| {
-                    row.col(|_| ());
-                    row.col(|ui| {
-                        egui::TextEdit::singleline(&mut **new_namespace)
-                            .hint_text("New namespace")
-                            .show(ui);
-                    });
-                    row.col(|ui| {
-                        if ui
-                            .add_enabled(
-                                !new_namespace.is_empty()
-                                    && !namespaces.visibilities.keys().contains(&**new_namespace),
-                                egui::Button::new("➕"),
-                            )
-                            .clicked()
+                ui.label(egui::RichText::new(format!("{name}{NAMESPACE_SEPARATOR}*")).code());
+                ui.label(format!(
+                    "{num_shown}/{} shown, {num_components} components",
+                    members.len()
+                ));
+                if ui
+                    .small_button("Show only")
+                    .on_hover_text("Show only the namespaces in this group")
+                    .clicked()
+                {
+                    for (ns, vis) in self.visibilities {
+                        let in_group = members.contains(&ns.as_str());
+                        if *vis != in_group {
+                            self.events.push(visibility_ev(ns, in_group));
+                        }
+                    }
+                }
+            })
+            .body(|ui| {
+                if let Some(ns) = &tree.namespace {
+                    self.namespace_ui(ui, ns, ns);
+                }
+                self.children_ui(ui, tree);
+            });
+        if toggle.clicked() && !self.expanded.remove(&tree.path) {
+            self.expanded.insert(tree.path.clone());
+        }
+    }
+
+    fn namespace_ui(&mut self, ui: &mut egui::Ui, name: &str, ns: &str) {
+        let vis = self.visibilities.get(ns).copied().unwrap_or_default();
+        let num_components = self.components.get(ns).copied().unwrap_or_default();
+        let locked = self.locked.contains(ns);
+        let colour = self
+            .colours
+            .get(ns)
+            .map(|c| egui::Color32::from_rgba_unmultiplied(c.r, c.g, c.b, c.a));
+        ui.horizontal(|ui| {
+            let mut new_vis = vis;
+            if ui.checkbox(&mut new_vis, "").changed() {
+                self.events.push(visibility_ev(ns, new_vis));
+            }
+            if ui
+                .selectable_label(locked, if locked { "🔒" } else { "🔓" })
+                .on_hover_text(if locked {
+                    "Locked: components cannot be edited and are not saved"
+                } else {
+                    "Lock"
+                })
+                .clicked()
+            {
+                self.events.push(ProjectEv::Lock {
+                    ns: ns.to_owned(),
+                    locked: !locked,
+                });
+            }
+            let mut label = egui::RichText::new(name).code();
+            if let Some(colour) = colour {
+                label = label.color(colour);
+            }
+            if ns == self.default_namespace {
+                label = label.strong();
+            }
+            ui.label(label).on_hover_text(ns);
+            if vis && self.dirty.contains(ns) {
+                ui.label("*").on_hover_text("Unsaved changes");
+            }
+            ui.label(if vis {
+                num_components.to_string()
+            } else {
+                "-".into()
+            });
+            ui.menu_button("…", |ui| {
+                ui.horizontal(|ui| {
+                    egui::TextEdit::singleline(&mut *self.rename_namespace)
+                        .hint_text("New name")
+                        .desired_width(100.0)
+                        .show(ui);
+                    if ui
+                        .add_enabled(
+                            ns != self.default_namespace
+                                && !self.rename_namespace.is_empty()
+                                && !self.visibilities.contains_key(&*self.rename_namespace),
+                            egui::Button::new("Rename"),
+                        )
+                        .clicked()
+                    {
+                        self.events.push(ProjectEv::Rename {
+                            from: ns.to_owned(),
+                            to: std::mem::take(&mut *self.rename_namespace),
+                            history_invoked: false,
+                        });
+                        ui.close_menu();
+                    }
+                });
+                ui.add_enabled_ui(vis && !locked && ns != self.default_namespace, |ui| {
+                    ui.menu_button("Merge into", |ui| {
+                        for other in self
+                            .visibilities
+                            .iter()
+                            .filter(|(other, vis)| {
+                                **vis && *other != ns && !self.locked.contains(*other)
+                            })
+                            .map(|(other, _)| other)
+                            .sorted()
                         {
-                            namespaces
-                                .visibilities
-                                .insert(new_namespace.to_owned(), true);
-
-                            commands.trigger(HistoryEv::one_history(HistoryEntry::Namespace {
-                                namespace: new_namespace.to_owned(),
-                                action: NamespaceAction::Create(None),
-                            }));
-                            new_namespace.clear();
+                            if ui.button(other.as_str()).clicked() {
+                                self.events.push(ProjectEv::Merge {
+                                    from: ns.to_owned(),
+                                    into: other.to_owned(),
+                                });
+                                ui.close_menu();
+                            }
                         }
                     });
                 });
+                if ui
+                    .add_enabled(
+                        vis && !locked && self.num_selected != 0,
+                        egui::Button::new(format!(
+                            "Move {} selected components here",
+                            self.num_selected
+                        )),
+                    )
+                    .clicked()
+                {
+                    self.events.push(ProjectEv::MoveSelected(ns.to_owned()));
+                    ui.close_menu();
+                }
+                if ui
+                    .add_enabled(
+                        ns != self.default_namespace,
+                        egui::Button::new("Make default namespace"),
+                    )
+                    .clicked()
+                {
+                    self.events.push(ProjectEv::SetDefault(ns.to_owned()));
+                    ui.close_menu();
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Colour");
+                    let mut new_colour = colour.unwrap_or_else(|| ui.visuals().text_color());
+                    if egui::color_picker::color_edit_button_srgba(
+                        ui,
+                        &mut new_colour,
+                        egui::color_picker::Alpha::Opaque,
+                    )
+                    .changed()
+                    {
+                        self.events.push(ProjectEv::SetColour {
+                            ns: ns.to_owned(),
+                            colour: Some(HexColor::rgb(
+                                new_colour.r(),
+                                new_colour.g(),
+                                new_colour.b(),
+                            )),
+                        });
+                    }
+                    if ui
+                        .add_enabled(colour.is_some(), egui::Button::new("Clear"))
+                        .clicked()
+                    {
+                        self.events.push(ProjectEv::SetColour {
+                            ns: ns.to_owned(),
+                            colour: None,
+                        });
+                    }
+                });
             });
+            if ui
+                .add_enabled(
+                    num_components == 0 && ns != self.default_namespace && !vis && !locked,
+                    egui::Button::new("❌").fill(egui::Color32::DARK_RED),
+                )
+                .clicked()
+            {
+                self.events.push(ProjectEv::Delete(ns.to_owned()));
+            }
+        });
+    }
+}
+
+fn visibility_ev(ns: &str, show: bool) -> ProjectEv {
+    if show {
+        ProjectEv::Show {
+            ns: ns.to_owned(),
+            history_invoked: false,
+            notif: true,
+        }
+    } else {
+        ProjectEv::Hide {
+            ns: ns.to_owned(),
+            history_invoked: false,
+            notif: true,
+        }
     }
 }
 
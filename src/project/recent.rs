@@ -0,0 +1,209 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dirs_paths::data_path,
+    file::{load_toml, save_toml},
+    project::{events::ProjectEv, Namespaces},
+    ui::popup::Popup,
+};
+
+const MAX_RECENT_PROJECTS: usize = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RecentProject {
+    pub dir: PathBuf,
+    pub opened: SystemTime,
+}
+
+impl RecentProject {
+    #[must_use]
+    pub fn opened_text(&self) -> String {
+        DateTime::<Local>::from(self.opened)
+            .format("%d/%m/%Y %H:%M")
+            .to_string()
+    }
+}
+
+/// Most recently opened project directories, newest first
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct RecentProjects {
+    pub projects: Vec<RecentProject>,
+    pub reopen_last: bool,
+}
+
+impl RecentProjects {
+    pub fn load() -> Self {
+        if !data_path("recent_projects.toml").exists() {
+            return Self::default();
+        }
+        match load_toml(&data_path("recent_projects.toml"), Some("recent projects")) {
+            Ok(str) => {
+                info!("Found recent projects file");
+                str
+            }
+            Err(e) => {
+                info!("Couldn't open or parse recent projects file: {e:?}");
+
+                Self::default()
+            }
+        }
+    }
+    pub fn save(&self) -> eyre::Result<()> {
+        save_toml(
+            self,
+            &data_path("recent_projects.toml"),
+            Some("recent projects"),
+        )
+    }
+    pub fn add(&mut self, dir: &Path) {
+        self.projects.retain(|a| a.dir != dir);
+        self.projects.insert(
+            0,
+            RecentProject {
+                dir: dir.to_owned(),
+                opened: SystemTime::now(),
+            },
+        );
+        self.projects.truncate(MAX_RECENT_PROJECTS);
+    }
+    #[must_use]
+    pub fn last(&self) -> Option<&RecentProject> {
+        self.projects.iter().find(|a| a.dir.is_dir())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Event)]
+pub enum RecentProjectsEv {
+    ReopenLast(bool),
+    Forget(PathBuf),
+    Clear,
+}
+
+#[expect(clippy::needless_pass_by_value)]
+pub fn on_recent_projects(trigger: Trigger<RecentProjectsEv>, mut recent: ResMut<RecentProjects>) {
+    match trigger.event() {
+        RecentProjectsEv::ReopenLast(reopen_last) => recent.reopen_last = *reopen_last,
+        RecentProjectsEv::Forget(dir) => recent.projects.retain(|a| a.dir != *dir),
+        RecentProjectsEv::Clear => recent.projects.clear(),
+    }
+    let _ = recent.save();
+}
+
+#[expect(clippy::needless_pass_by_value)]
+pub fn on_project_opened(trigger: Trigger<ProjectEv>, mut recent: ResMut<RecentProjects>) {
+    let ProjectEv::Load(dir, false) = trigger.event() else {
+        return;
+    };
+    if *dir == Namespaces::default().dir {
+        return;
+    }
+    recent.add(dir);
+    let _ = recent.save();
+}
+
+fn project_button(ui: &mut egui::Ui, commands: &mut Commands, project: &RecentProject) -> bool {
+    let exists = project.dir.is_dir();
+    let response = ui
+        .add_enabled(
+            exists,
+            egui::Button::new(project.dir.to_string_lossy().into_owned()),
+        )
+        .on_hover_text(format!("Last opened {}", project.opened_text()))
+        .on_disabled_hover_text("Directory not found");
+    if response.clicked() {
+        commands.trigger(ProjectEv::Switch(project.dir.clone()));
+    }
+    response.clicked()
+}
+
+pub fn recent_projects_menu(ui: &mut egui::Ui, commands: &mut Commands, recent: &RecentProjects) {
+    ui.menu_button("Open Recent", |ui| {
+        if recent.projects.is_empty() {
+            ui.label("No recent projects");
+        }
+        for project in &recent.projects {
+            if project_button(ui, commands, project) {
+                ui.close_menu();
+            }
+        }
+        ui.separator();
+        let mut reopen_last = recent.reopen_last;
+        if ui
+            .checkbox(&mut reopen_last, "Reopen last project on startup")
+            .changed()
+        {
+            commands.trigger(RecentProjectsEv::ReopenLast(reopen_last));
+        }
+        if ui
+            .add_enabled(!recent.projects.is_empty(), egui::Button::new("Clear"))
+            .clicked()
+        {
+            commands.trigger(RecentProjectsEv::Clear);
+        }
+    });
+}
+
+/// Lists recent projects to open at startup, instead of the scratchpad
+#[must_use]
+pub fn launcher_popup(recent: &RecentProjects) -> Popup {
+    Popup::new(
+        "project_launcher",
+        || {
+            egui::Window::new("Open a project")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        },
+        |state, ui, commands, shown| {
+            let mut state = state.lock().unwrap();
+            let recent: &mut RecentProjects = state.downcast_mut().unwrap();
+
+            let mut forget = None;
+            egui::Grid::new("project_launcher")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    for project in &recent.projects {
+                        if project_button(ui, commands, project) {
+                            *shown = false;
+                        }
+                        ui.label(project.opened_text());
+                        if ui.small_button("❌").on_hover_text("Forget").clicked() {
+                            forget = Some(project.dir.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+            if let Some(forget) = forget {
+                recent.projects.retain(|a| a.dir != forget);
+                commands.trigger(RecentProjectsEv::Forget(forget));
+            }
+            if ui
+                .checkbox(&mut recent.reopen_last, "Reopen last project on startup")
+                .changed()
+            {
+                commands.trigger(RecentProjectsEv::ReopenLast(recent.reopen_last));
+            }
+            ui.horizontal(|ui| {
+                if ui.button("Open...").clicked() {
+                    commands.trigger(ProjectEv::Open);
+                    *shown = false;
+                }
+                if ui.button("Use scratchpad").clicked() {
+                    *shown = false;
+                }
+            });
+        },
+        Mutex::new(Box::new(recent.to_owned())),
+    )
+}
@@ -0,0 +1,100 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dirs_paths::data_dir,
+    file::{load_toml, save_toml},
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IdScheme {
+    #[default]
+    Random,
+    Sequential,
+    Slug,
+}
+
+impl IdScheme {
+    pub const ALL: [Self; 3] = [Self::Random, Self::Sequential, Self::Slug];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Random => "Random",
+            Self::Sequential => "Sequential",
+            Self::Slug => "From display name",
+        }
+    }
+}
+
+/// Settings that are kept per user and project, stored in the data directory under the project's path
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ProjectSettings {
+    pub expanded_groups: BTreeSet<String>,
+    pub locked_namespaces: BTreeSet<String>,
+}
+
+impl ProjectSettings {
+    /// Named after a hash of the canonical path, so that different directories never share a file
+    fn path(dir: &Path) -> PathBuf {
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_owned());
+        // FNV-1a, which unlike `DefaultHasher` does not change between Rust versions
+        let hash = dir
+            .as_os_str()
+            .as_encoded_bytes()
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, b| {
+                (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+            });
+        let name = dir
+            .file_name()
+            .map(|a| a.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        data_dir("project_settings").join(format!("{name}-{hash:016x}.toml"))
+    }
+    #[must_use]
+    pub fn load(dir: &Path) -> Self {
+        let path = Self::path(dir);
+        if !path.exists() {
+            return Self::default();
+        }
+        match load_toml(&path, Some("project settings")) {
+            Ok(str) => {
+                info!(?dir, "Found project settings file");
+                str
+            }
+            Err(e) => {
+                info!("Couldn't open or parse project settings file: {e:?}");
+
+                Self::default()
+            }
+        }
+    }
+    pub fn save(&self, dir: &Path) -> eyre::Result<()> {
+        save_toml(self, &Self::path(dir), Some("project settings"))
+    }
+}
+
+#[must_use]
+pub fn slugify(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .split('-')
+        .filter(|a| !a.is_empty())
+        .join("-")
+}
@@ -0,0 +1,265 @@
+pub mod server;
+
+use std::collections::HashSet;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use egui_notify::ToastLevel;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+
+use crate::{
+    component::{
+        actions::{rendering::RenderEv, selecting::SelectedComponent},
+        make_component,
+        pla2::{MCCoords, PlaComponent},
+        skin::Skin,
+    },
+    history::{HistoryEntry, HistoryEv},
+    misc_config::settings::INIT_MISC_SETTINGS,
+    project::Namespaces,
+    rpc::server::{RpcError, RpcServer},
+    state::IntoSystemConfigExt,
+    ui::{
+        map::{mouse_nav::JumpToEv, zoom::SkinZoom},
+        notif::{NotifLogRwLockExt, NOTIF_LOG},
+    },
+};
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ListComponentsParams {
+    namespace: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ComponentsParams {
+    components: Vec<PlaComponent<MCCoords>>,
+}
+
+#[derive(Deserialize)]
+struct PanCameraParams {
+    x: f32,
+    z: f32,
+    zoom: Option<f32>,
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| RpcError::new(RpcError::INVALID_PARAMS, e))
+}
+
+#[derive(SystemParam)]
+pub struct RpcParams<'w, 's> {
+    commands: Commands<'w, 's>,
+    components: Query<'w, 's, (Entity, &'static PlaComponent)>,
+    selected: Query<'w, 's, &'static PlaComponent, With<SelectedComponent>>,
+    namespaces: Res<'w, Namespaces>,
+    skin: Res<'w, Skin>,
+    skin_zoom: Res<'w, SkinZoom>,
+}
+
+impl RpcParams<'_, '_> {
+    fn handle(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "list_components" => {
+                let ListComponentsParams { namespace } = parse_params(params)?;
+                Ok(json!(self
+                    .components
+                    .iter()
+                    .filter(|(_, a)| namespace.as_ref().is_none_or(|ns| a.namespace == *ns))
+                    .map(|(_, a)| a.to_mc_coords())
+                    .collect::<Vec<_>>()))
+            }
+            "get_selection" => Ok(json!(self
+                .selected
+                .iter()
+                .map(PlaComponent::to_mc_coords)
+                .collect::<Vec<_>>())),
+            "add_components" => {
+                let ComponentsParams { components } = parse_params(params)?;
+                let mut taken = self
+                    .components
+                    .iter()
+                    .map(|(_, a)| (a.namespace.clone(), a.id.clone()))
+                    .collect::<HashSet<_>>();
+                for pla in &components {
+                    self.check_editable(pla)?;
+                    if pla.id.is_empty() || !taken.insert((pla.namespace.clone(), pla.id.clone())) {
+                        return Err(RpcError::new(
+                            RpcError::INVALID_PARAMS,
+                            format!("Component {pla} has an empty or duplicate id"),
+                        ));
+                    }
+                }
+                let histories = components
+                    .into_iter()
+                    .map(|pla| {
+                        let pla = pla.to_editor_coords();
+                        let e = self
+                            .commands
+                            .spawn(make_component(pla.clone(), &self.skin, *self.skin_zoom))
+                            .trigger(RenderEv::default())
+                            .id();
+                        HistoryEntry::Component {
+                            e,
+                            before: None,
+                            after: Some(pla.into()),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let added = histories.len();
+                if added != 0 {
+                    self.commands.trigger(HistoryEv::NewHistory(histories));
+                }
+                Ok(json!({ "added": added }))
+            }
+            "update_components" => {
+                let ComponentsParams { components } = parse_params(params)?;
+                let mut histories = Vec::new();
+                for pla in components {
+                    self.check_editable(&pla)?;
+                    let Some((e, old)) = self
+                        .components
+                        .iter()
+                        .find(|(_, a)| a.namespace == pla.namespace && a.id == pla.id)
+                    else {
+                        return Err(RpcError::new(
+                            RpcError::INVALID_PARAMS,
+                            format!("Component {pla} does not exist"),
+                        ));
+                    };
+                    let mut new = pla.to_editor_coords();
+                    new.attributes.clone_from(&old.attributes);
+                    histories.push(HistoryEntry::Component {
+                        e,
+                        before: Some(old.to_owned().into()),
+                        after: Some(new.into()),
+                    });
+                }
+                for history in &histories {
+                    if let HistoryEntry::Component {
+                        e,
+                        after: Some(new),
+                        ..
+                    } = history
+                    {
+                        self.commands
+                            .entity(*e)
+                            .insert((**new).clone())
+                            .trigger(RenderEv::default());
+                    }
+                }
+                let updated = histories.len();
+                if updated != 0 {
+                    self.commands.trigger(HistoryEv::NewHistory(histories));
+                }
+                Ok(json!({ "updated": updated }))
+            }
+            "pan_camera" => {
+                let PanCameraParams { x, z, zoom } = parse_params(params)?;
+                self.commands.trigger(JumpToEv {
+                    pos: Vec2::new(x, z),
+                    zoom,
+                });
+                Ok(Value::Null)
+            }
+            _ => Err(RpcError::new(
+                RpcError::METHOD_NOT_FOUND,
+                format!("Unknown method {method}"),
+            )),
+        }
+    }
+
+    fn check_editable(&self, pla: &PlaComponent<MCCoords>) -> Result<(), RpcError> {
+        if pla.nodes.is_empty() {
+            return Err(RpcError::new(
+                RpcError::INVALID_PARAMS,
+                format!("Component {pla} has no nodes"),
+            ));
+        }
+        if self.namespaces.visibilities.get(&pla.namespace) != Some(&true) {
+            return Err(RpcError::new(
+                RpcError::SERVER_ERROR,
+                format!("Namespace {} is not visible", pla.namespace),
+            ));
+        }
+        if self.namespaces.locked.contains(&pla.namespace) {
+            return Err(RpcError::new(
+                RpcError::SERVER_ERROR,
+                format!("Namespace {} is locked", pla.namespace),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[tracing::instrument(skip_all)]
+pub fn handle_rpc_requests_sy(server: Res<RpcServer>, mut params: RpcParams) {
+    for incoming in server.requests() {
+        debug!(method = ?incoming.request.method, "Handling RPC request");
+        let result = params.handle(&incoming.request.method, incoming.request.params.to_owned());
+        incoming.respond(result);
+    }
+}
+
+#[tracing::instrument(skip_all)]
+pub fn notify_selection_sy(
+    server: Res<RpcServer>,
+    added: Query<(), Added<SelectedComponent>>,
+    mut removed: RemovedComponents<SelectedComponent>,
+    selected: Query<&PlaComponent, With<SelectedComponent>>,
+) {
+    if added.is_empty() && removed.read().count() == 0 {
+        return;
+    }
+    server.notify(
+        "selection_changed",
+        json!(selected
+            .iter()
+            .map(PlaComponent::to_mc_coords)
+            .collect::<Vec<_>>()),
+    );
+}
+
+#[expect(clippy::needless_pass_by_value)]
+pub fn on_history_notify(trigger: Trigger<HistoryEv>, server: Res<RpcServer>) {
+    server.notify(
+        "history_changed",
+        match trigger.event() {
+            HistoryEv::NewHistory(histories) | HistoryEv::Coalesce(histories) => json!({
+                "action": "new",
+                "entries": histories.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            }),
+            HistoryEv::Undo => json!({ "action": "undo" }),
+            HistoryEv::Redo => json!({ "action": "redo" }),
+        },
+    );
+}
+
+pub struct RpcPlugin;
+
+impl Plugin for RpcPlugin {
+    fn build(&self, app: &mut App) {
+        let port = INIT_MISC_SETTINGS.rpc_port;
+        if port == 0 {
+            return;
+        }
+        let server = match RpcServer::start(port) {
+            Ok(server) => server,
+            Err(e) => {
+                error!("Couldn't start RPC server on port {port}: {e:?}");
+                NOTIF_LOG.push(
+                    format!("Couldn't start automation server on port {port}:\n{e}"),
+                    ToastLevel::Error,
+                );
+                return;
+            }
+        };
+        app.insert_resource(server)
+            .add_systems(
+                Update,
+                (handle_rpc_requests_sy, notify_selection_sy).run_if_not_loading(),
+            )
+            .add_observer(on_history_notify);
+    }
+}
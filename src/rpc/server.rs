@@ -0,0 +1,240 @@
+use std::{
+    fmt::Display,
+    fs::OpenOptions,
+    io::{BufRead, BufReader, Write},
+    net::{Shutdown, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use rand::distr::{Alphanumeric, SampleString};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::dirs_paths::data_path;
+
+/// Outgoing messages to a client, written to its socket by the client's own thread
+pub type Client = Arc<mpsc::Sender<String>>;
+
+/// A JSON-RPC 2.0 request, sent as a single line of JSON
+#[derive(Deserialize, Clone, Debug)]
+pub struct RpcRequest {
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RpcError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl RpcError {
+    pub const PARSE_ERROR: i32 = -32700;
+    pub const METHOD_NOT_FOUND: i32 = -32601;
+    pub const INVALID_PARAMS: i32 = -32602;
+    pub const SERVER_ERROR: i32 = -32000;
+    pub const UNAUTHORISED: i32 = -32001;
+
+    #[must_use]
+    pub fn new(code: i32, message: impl Display) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+pub struct IncomingRequest {
+    pub request: RpcRequest,
+    pub client: Client,
+}
+
+impl IncomingRequest {
+    pub fn respond(&self, result: Result<Value, RpcError>) {
+        let Some(id) = &self.request.id else {
+            return;
+        };
+        let _ = self.client.send(response(id.to_owned(), result));
+    }
+}
+
+#[derive(Deserialize)]
+struct AuthParams {
+    token: String,
+}
+
+/// Local server that external tools connect to, one JSON-RPC message per line.
+/// Clients must first send an `auth` request with the token in the data directory
+#[derive(Resource)]
+pub struct RpcServer {
+    requests: Mutex<mpsc::Receiver<IncomingRequest>>,
+    clients: Arc<Mutex<Vec<Client>>>,
+}
+
+impl RpcServer {
+    #[must_use]
+    pub fn token_path() -> PathBuf {
+        data_path("rpc_token")
+    }
+
+    pub fn start(port: u16) -> std::io::Result<Self> {
+        let token = Alphanumeric.sample_string(&mut rand::rng(), 32);
+        write_token(&Self::token_path(), &token)?;
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        let (tx, rx) = mpsc::channel();
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        let thread_clients = Arc::clone(&clients);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if let Err(e) =
+                    stream.and_then(|stream| accept(stream, &tx, &thread_clients, &token))
+                {
+                    warn!("Couldn't accept RPC client: {e:?}");
+                }
+            }
+        });
+        info!(port, "Started RPC server");
+        Ok(Self {
+            requests: Mutex::new(rx),
+            clients,
+        })
+    }
+
+    #[must_use]
+    pub fn requests(&self) -> Vec<IncomingRequest> {
+        self.requests.lock().unwrap().try_iter().collect()
+    }
+
+    /// Sends a notification to every connected client, dropping those that have disconnected
+    pub fn notify(&self, method: &str, params: Value) {
+        let message = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+        })
+        .to_string();
+        self.clients
+            .lock()
+            .unwrap()
+            .retain(|client| client.send(message.clone()).is_ok());
+    }
+}
+
+fn check_auth(request: &RpcRequest, token: &str) -> Result<Value, RpcError> {
+    if request.method != "auth" {
+        return Err(RpcError::new(
+            RpcError::UNAUTHORISED,
+            "The first request must be auth",
+        ));
+    }
+    match serde_json::from_value::<AuthParams>(request.params.to_owned()) {
+        Ok(params) if params.token == token => Ok(Value::Null),
+        Ok(_) => Err(RpcError::new(RpcError::UNAUTHORISED, "Wrong token")),
+        Err(e) => Err(RpcError::new(RpcError::INVALID_PARAMS, e)),
+    }
+}
+
+fn accept(
+    stream: TcpStream,
+    tx: &mpsc::Sender<IncomingRequest>,
+    clients: &Arc<Mutex<Vec<Client>>>,
+    token: &str,
+) -> std::io::Result<()> {
+    info!(addr = ?stream.peer_addr(), "RPC client connected");
+    stream.set_write_timeout(Some(Duration::from_secs(1)))?;
+    let mut writer = stream.try_clone()?;
+    let (client_tx, client_rx) = mpsc::channel::<String>();
+    let client = Arc::new(client_tx);
+    // Stops once every sender is dropped, after writing what is still queued
+    std::thread::spawn(move || {
+        for message in client_rx {
+            if write_line(&mut writer, &message).is_err() {
+                break;
+            }
+        }
+        let _ = writer.shutdown(Shutdown::Both);
+    });
+    let clients = Arc::clone(clients);
+    let token = token.to_owned();
+    let tx = tx.clone();
+    std::thread::spawn(move || {
+        let mut authed = false;
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let request = match serde_json::from_str::<RpcRequest>(&line) {
+                Ok(request) => request,
+                Err(e) => {
+                    let _ = client.send(response(
+                        Value::Null,
+                        Err(RpcError::new(RpcError::PARSE_ERROR, e)),
+                    ));
+                    break;
+                }
+            };
+            if !authed {
+                let result = check_auth(&request, &token);
+                authed = result.is_ok();
+                let id = request.id.unwrap_or_default();
+                let _ = client.send(response(id, result));
+                if !authed {
+                    warn!("RPC client failed to authenticate");
+                    break;
+                }
+                clients.lock().unwrap().push(Arc::clone(&client));
+                continue;
+            }
+            let client = Arc::clone(&client);
+            if tx.send(IncomingRequest { request, client }).is_err() {
+                break;
+            }
+        }
+        clients.lock().unwrap().retain(|a| !Arc::ptr_eq(a, &client));
+        info!("RPC client disconnected");
+    });
+    Ok(())
+}
+
+fn response(id: Value, result: Result<Value, RpcError>) -> String {
+    match result {
+        Ok(result) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": result,
+        }),
+        Err(error) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": error,
+        }),
+    }
+    .to_string()
+}
+
+/// Writes the token so that only the current user can read it
+fn write_token(path: &Path, token: &str) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(token.as_bytes())
+}
+
+fn write_line(stream: &mut TcpStream, message: &str) -> std::io::Result<()> {
+    stream.write_all(message.as_bytes())?;
+    stream.write_all(b"\n")?;
+    stream.flush()
+}
@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    scripting::{ScriptEv, ScriptOutput},
+    ui::panel::dock::{open_dock_window, DockLayout, DockWindow, PanelParams},
+};
+
+const REFERENCE: &str = "\
+components() / selected(): all loaded / selected components
+new_component(namespace, type): a component to fill in and add()
+update(c), add(c), delete(c): record a change, applied when the script finishes
+
+c.namespace, c.id, c.display_name, c.description, c.type, c.layer, c.tags
+c.nodes: array of [x, z]
+c.selected, c.is_new: read only
+
+c.length(), c.area(), c.centre(), c.translate(dx, dz)
+distance(x1, z1, x2, z2)";
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct ScriptConsole;
+
+#[derive(Clone, Copy, Event)]
+pub struct OpenScriptConsoleEv;
+
+impl DockWindow for ScriptConsole {
+    fn title(self) -> String {
+        "Scripting".into()
+    }
+    fn ui(self, params: &mut PanelParams, ui: &mut egui::Ui) {
+        let PanelParams {
+            scripts, commands, ..
+        } = params;
+        ui.horizontal(|ui| {
+            if ui.button("Run").clicked() {
+                commands.trigger(ScriptEv::Run);
+            }
+            egui::TextEdit::singleline(&mut scripts.name)
+                .hint_text("Script name")
+                .desired_width(120.0)
+                .show(ui);
+            if ui
+                .add_enabled(!scripts.name.is_empty(), egui::Button::new("Save"))
+                .on_hover_text("Save to the scripts folder in the data directory")
+                .clicked()
+            {
+                commands.trigger(ScriptEv::Save);
+            }
+            ui.menu_button("Open", |ui| {
+                if scripts.saved.is_empty() {
+                    ui.label("No saved scripts");
+                }
+                for name in &scripts.saved {
+                    ui.horizontal(|ui| {
+                        if ui.button(name).clicked() {
+                            commands.trigger(ScriptEv::Load(name.to_owned()));
+                            ui.close_menu();
+                        }
+                        if ui.small_button("🗑").on_hover_text("Delete").clicked() {
+                            commands.trigger(ScriptEv::Delete(name.to_owned()));
+                        }
+                    });
+                }
+            });
+            if ui.button("Clear output").clicked() {
+                scripts.output.clear();
+            }
+        });
+        ui.collapsing("Reference", |ui| {
+            ui.label(egui::RichText::new(REFERENCE).monospace());
+        });
+        egui::TextEdit::multiline(&mut scripts.code)
+            .code_editor()
+            .desired_rows(12)
+            .desired_width(f32::INFINITY)
+            .show(ui);
+        ui.separator();
+        egui::ScrollArea::vertical()
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for output in &scripts.output {
+                    match output {
+                        ScriptOutput::Print(s) => {
+                            ui.label(egui::RichText::new(s).monospace());
+                        }
+                        ScriptOutput::Success(s) => {
+                            ui.colored_label(egui::Color32::GREEN, s);
+                        }
+                        ScriptOutput::Error(s) => {
+                            ui.colored_label(egui::Color32::RED, s);
+                        }
+                    }
+                }
+            });
+    }
+}
+
+pub fn on_script_console(_trigger: Trigger<OpenScriptConsoleEv>, mut state: ResMut<DockLayout>) {
+    open_dock_window(&mut state, ScriptConsole);
+}
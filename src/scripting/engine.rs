@@ -0,0 +1,292 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use bevy::prelude::*;
+use itertools::Itertools;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, FLOAT};
+
+use crate::component::pla2::{MCCoords, PlaComponent};
+
+/// Stops runaway scripts from freezing the editor, since they run on the main thread
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_DURATION: Duration = Duration::from_secs(1);
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// A component as seen by scripts, in Minecraft coordinates
+#[derive(Clone, Debug)]
+pub struct ScriptComponent {
+    pub entity: Option<Entity>,
+    pub selected: bool,
+    pub pla: PlaComponent<MCCoords>,
+}
+
+/// Changes requested by a script, applied together once it finishes
+#[derive(Default, Debug)]
+pub struct ScriptChanges {
+    pub updated: HashMap<Entity, PlaComponent<MCCoords>>,
+    pub created: Vec<PlaComponent<MCCoords>>,
+    pub deleted: HashSet<Entity>,
+}
+
+impl ScriptChanges {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.updated.is_empty() && self.created.is_empty() && self.deleted.is_empty()
+    }
+}
+
+fn to_float(value: &Dynamic) -> ScriptResult<FLOAT> {
+    value
+        .as_float()
+        .or_else(|_| value.as_int().map(|a| a as FLOAT))
+        .map_err(|ty| format!("Expected a number, got {ty}").into())
+}
+
+fn get_nodes(c: &mut ScriptComponent) -> Array {
+    c.pla
+        .nodes
+        .iter()
+        .map(|a| {
+            Dynamic::from_array(vec![
+                Dynamic::from_float(FLOAT::from(a.0.x)),
+                Dynamic::from_float(FLOAT::from(a.0.y)),
+            ])
+        })
+        .collect()
+}
+
+fn set_nodes(c: &mut ScriptComponent, nodes: Array) -> ScriptResult<()> {
+    c.pla.nodes = nodes
+        .into_iter()
+        .map(|node| {
+            let node = node
+                .into_array()
+                .map_err(|ty| format!("Expected a node [x, z], got {ty}"))?;
+            let [x, z] = node.as_slice() else {
+                return Err(format!("Expected a node [x, z], got {} numbers", node.len()).into());
+            };
+            Ok(MCCoords(Vec2::new(
+                to_float(x)? as f32,
+                to_float(z)? as f32,
+            )))
+        })
+        .collect::<ScriptResult<_>>()?;
+    Ok(())
+}
+
+fn component_length(c: &mut ScriptComponent) -> FLOAT {
+    c.pla
+        .nodes
+        .iter()
+        .tuple_windows()
+        .map(|(a, b)| FLOAT::from(a.0.distance(b.0)))
+        .sum()
+}
+
+fn component_area(c: &mut ScriptComponent) -> FLOAT {
+    let nodes = &c.pla.nodes;
+    let twice_area = nodes
+        .iter()
+        .zip(nodes.iter().cycle().skip(1))
+        .map(|(a, b)| FLOAT::from(a.0.perp_dot(b.0)))
+        .sum::<FLOAT>();
+    twice_area.abs() / 2.0
+}
+
+fn component_centre(c: &mut ScriptComponent) -> Array {
+    let sum = c.pla.nodes.iter().map(|a| a.0).sum::<Vec2>();
+    let centre = sum / c.pla.nodes.len().max(1) as f32;
+    vec![
+        Dynamic::from_float(FLOAT::from(centre.x)),
+        Dynamic::from_float(FLOAT::from(centre.y)),
+    ]
+}
+
+#[expect(clippy::needless_pass_by_value)]
+fn translate(c: &mut ScriptComponent, dx: Dynamic, dz: Dynamic) -> ScriptResult<()> {
+    let delta = Vec2::new(to_float(&dx)? as f32, to_float(&dz)? as f32);
+    for node in &mut c.pla.nodes {
+        node.0 += delta;
+    }
+    Ok(())
+}
+
+#[expect(clippy::needless_pass_by_value)]
+fn distance(x1: Dynamic, z1: Dynamic, x2: Dynamic, z2: Dynamic) -> ScriptResult<FLOAT> {
+    Ok((to_float(&x1)? - to_float(&x2)?).hypot(to_float(&z1)? - to_float(&z2)?))
+}
+
+fn register_component(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<ScriptComponent>("Component")
+        .register_get_set(
+            "namespace",
+            |c: &mut ScriptComponent| c.pla.namespace.clone(),
+            |c: &mut ScriptComponent, v: String| c.pla.namespace = v,
+        )
+        .register_get_set(
+            "id",
+            |c: &mut ScriptComponent| c.pla.id.clone(),
+            |c: &mut ScriptComponent, v: String| c.pla.id = v,
+        )
+        .register_get_set(
+            "display_name",
+            |c: &mut ScriptComponent| c.pla.display_name.clone(),
+            |c: &mut ScriptComponent, v: String| c.pla.display_name = v,
+        )
+        .register_get_set(
+            "description",
+            |c: &mut ScriptComponent| c.pla.description.clone(),
+            |c: &mut ScriptComponent, v: String| c.pla.description = v,
+        )
+        .register_get_set(
+            "type",
+            |c: &mut ScriptComponent| c.pla.ty.clone(),
+            |c: &mut ScriptComponent, v: String| c.pla.ty = v,
+        )
+        .register_get_set(
+            "layer",
+            |c: &mut ScriptComponent| Dynamic::from_float(FLOAT::from(c.pla.layer)),
+            |c: &mut ScriptComponent, v: Dynamic| -> ScriptResult<()> {
+                c.pla.layer = to_float(&v)? as f32;
+                Ok(())
+            },
+        )
+        .register_get_set(
+            "tags",
+            |c: &mut ScriptComponent| {
+                c.pla
+                    .tags
+                    .iter()
+                    .cloned()
+                    .map(Dynamic::from)
+                    .collect::<Array>()
+            },
+            |c: &mut ScriptComponent, v: Array| -> ScriptResult<()> {
+                c.pla.tags = v
+                    .into_iter()
+                    .map(|a| {
+                        a.into_string()
+                            .map_err(|ty| format!("Expected a string tag, got {ty}").into())
+                    })
+                    .collect::<ScriptResult<_>>()?;
+                Ok(())
+            },
+        )
+        .register_get_set("nodes", get_nodes, set_nodes)
+        .register_get("selected", |c: &mut ScriptComponent| c.selected)
+        .register_get("is_new", |c: &mut ScriptComponent| c.entity.is_none())
+        .register_fn("to_string", |c: &mut ScriptComponent| c.pla.to_string())
+        .register_fn("to_debug", |c: &mut ScriptComponent| c.pla.to_string())
+        .register_fn("length", component_length)
+        .register_fn("area", component_area)
+        .register_fn("centre", component_centre)
+        .register_fn("translate", translate)
+        .register_fn("distance", distance);
+}
+
+/// Runs `code` against a snapshot of `components`, collecting printed lines into `output`
+pub fn run_script(
+    code: &str,
+    components: Vec<ScriptComponent>,
+    output: &Rc<RefCell<Vec<String>>>,
+) -> ScriptResult<ScriptChanges> {
+    let components = Rc::new(components);
+    let changes = Rc::new(RefCell::new(ScriptChanges::default()));
+
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    let start = Instant::now();
+    engine.on_progress(move |_| {
+        (start.elapsed() > MAX_DURATION)
+            .then(|| Dynamic::from(format!("Script ran for over {MAX_DURATION:?}")))
+    });
+    register_component(&mut engine);
+    {
+        let output = Rc::clone(output);
+        engine.on_print(move |s| output.borrow_mut().push(s.to_owned()));
+    }
+    {
+        let output = Rc::clone(output);
+        engine.on_debug(move |s, _, pos| output.borrow_mut().push(format!("[{pos}] {s}")));
+    }
+    {
+        let components = Rc::clone(&components);
+        engine.register_fn("components", move || {
+            components
+                .iter()
+                .cloned()
+                .map(Dynamic::from)
+                .collect::<Array>()
+        });
+    }
+    {
+        let components = Rc::clone(&components);
+        engine.register_fn("selected", move || {
+            components
+                .iter()
+                .filter(|a| a.selected)
+                .cloned()
+                .map(Dynamic::from)
+                .collect::<Array>()
+        });
+    }
+    engine.register_fn("new_component", |namespace: &str, ty: &str| {
+        ScriptComponent {
+            entity: None,
+            selected: false,
+            pla: PlaComponent {
+                namespace: namespace.into(),
+                ty: ty.into(),
+                ..default()
+            },
+        }
+    });
+    {
+        let changes = Rc::clone(&changes);
+        engine.register_fn("update", move |c: ScriptComponent| -> ScriptResult<()> {
+            let Some(e) = c.entity else {
+                return Err(format!("{} has not been added yet, use add()", c.pla).into());
+            };
+            let mut changes = changes.borrow_mut();
+            if changes.deleted.contains(&e) {
+                return Err(format!("{} has been deleted", c.pla).into());
+            }
+            changes.updated.insert(e, c.pla);
+            Ok(())
+        });
+    }
+    {
+        let changes = Rc::clone(&changes);
+        engine.register_fn("add", move |c: ScriptComponent| -> ScriptResult<()> {
+            if c.entity.is_some() {
+                return Err(format!("{} already exists, use update()", c.pla).into());
+            }
+            changes.borrow_mut().created.push(c.pla);
+            Ok(())
+        });
+    }
+    {
+        let changes = Rc::clone(&changes);
+        engine.register_fn("delete", move |c: ScriptComponent| -> ScriptResult<()> {
+            let Some(e) = c.entity else {
+                return Err(format!("{} has not been added yet", c.pla).into());
+            };
+            let mut changes = changes.borrow_mut();
+            changes.updated.remove(&e);
+            changes.deleted.insert(e);
+            Ok(())
+        });
+    }
+
+    engine.run(code)?;
+    drop(engine);
+    Ok(Rc::try_unwrap(changes)
+        .map(RefCell::into_inner)
+        .unwrap_or_default())
+}
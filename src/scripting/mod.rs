@@ -0,0 +1,314 @@
+pub mod console;
+pub mod engine;
+
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use bevy::prelude::*;
+use egui_notify::ToastLevel;
+use engine::{run_script, ScriptChanges, ScriptComponent};
+
+use crate::{
+    component::{
+        actions::{rendering::RenderEv, selecting::SelectedComponent},
+        make_component,
+        pla2::PlaComponent,
+        skin::Skin,
+    },
+    dirs_paths::data_dir,
+    file::safe_delete,
+    history::{HistoryEntry, HistoryEv},
+    project::{manifest::ProjectManifest, Namespaces},
+    ui::{
+        map::zoom::SkinZoom,
+        notif::{NotifLogRwLockExt, NOTIF_LOG},
+    },
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScriptOutput {
+    Print(String),
+    Success(String),
+    Error(String),
+}
+
+/// State of the scripting console, and the names of scripts saved in the data directory
+#[derive(Resource, Default)]
+pub struct Scripts {
+    pub code: String,
+    pub name: String,
+    pub saved: Vec<String>,
+    pub output: Vec<ScriptOutput>,
+}
+
+impl Scripts {
+    pub const EXTENSION: &'static str = "rhai";
+
+    #[must_use]
+    pub fn dir() -> PathBuf {
+        data_dir("scripts")
+    }
+    #[must_use]
+    pub fn path(name: &str) -> PathBuf {
+        Self::dir().join(format!("{name}.{}", Self::EXTENSION))
+    }
+    #[must_use]
+    pub fn load() -> Self {
+        let mut scripts = Self::default();
+        scripts.refresh();
+        scripts
+    }
+    pub fn refresh(&mut self) {
+        self.saved = Self::dir()
+            .read_dir()
+            .map(|rd| {
+                rd.filter_map(Result::ok)
+                    .map(|a| a.path())
+                    .filter(|a| a.extension().is_some_and(|a| a == Self::EXTENSION))
+                    .filter_map(|a| Some(a.file_stem()?.to_string_lossy().into_owned()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.saved.sort();
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Event)]
+pub enum ScriptEv {
+    Run,
+    Save,
+    Load(String),
+    Delete(String),
+}
+
+fn check_editable(namespaces: &Namespaces, ns: &str) -> Result<(), String> {
+    if namespaces.visibilities.get(ns) != Some(&true) {
+        return Err(format!("Namespace {ns} is not visible"));
+    }
+    if namespaces.locked.contains(ns) {
+        return Err(format!("Namespace {ns} is locked"));
+    }
+    Ok(())
+}
+
+fn check_component(pla: &PlaComponent) -> Result<(), String> {
+    if pla.nodes.is_empty() {
+        return Err(format!("{pla} has no nodes"));
+    }
+    if pla.id.is_empty() {
+        return Err(format!("{pla} has an empty id"));
+    }
+    Ok(())
+}
+
+/// Validates everything a script changed, then applies it as one undoable step
+fn apply_changes(
+    changes: ScriptChanges,
+    commands: &mut Commands,
+    query: &Query<(Entity, &PlaComponent, Has<SelectedComponent>)>,
+    namespaces: &Namespaces,
+    manifest: &ProjectManifest,
+    skin: &Skin,
+    skin_zoom: SkinZoom,
+) -> Result<usize, String> {
+    let ScriptChanges {
+        updated,
+        created,
+        deleted,
+    } = changes;
+    let mut taken = query
+        .iter()
+        .filter(|(e, _, _)| !deleted.contains(e) && !updated.contains_key(e))
+        .map(|(_, a, _)| (a.namespace.clone(), a.id.clone()))
+        .collect::<HashSet<_>>();
+    let mut histories = Vec::new();
+
+    for e in deleted {
+        let (_, pla, _) = query.get(e).map_err(|e| e.to_string())?;
+        check_editable(namespaces, &pla.namespace)?;
+        histories.push(HistoryEntry::Component {
+            e,
+            before: Some(pla.to_owned().into()),
+            after: None,
+        });
+    }
+    for (e, new) in updated {
+        let (_, old, _) = query.get(e).map_err(|e| e.to_string())?;
+        let mut new = new.to_editor_coords();
+        new.attributes.clone_from(&old.attributes);
+        check_editable(namespaces, &old.namespace)?;
+        check_editable(namespaces, &new.namespace)?;
+        check_component(&new)?;
+        if !taken.insert((new.namespace.clone(), new.id.clone())) {
+            return Err(format!("{new} has the same id as another component"));
+        }
+        if *old != new {
+            histories.push(HistoryEntry::Component {
+                e,
+                before: Some(old.to_owned().into()),
+                after: Some(new.into()),
+            });
+        }
+    }
+    let mut new_components = Vec::new();
+    for new in created {
+        let mut new = new.to_editor_coords();
+        check_editable(namespaces, &new.namespace)?;
+        if new.id.is_empty() {
+            let namespace_ids = taken
+                .iter()
+                .filter(|(ns, _)| *ns == new.namespace)
+                .map(|(_, id)| id.as_str())
+                .collect::<HashSet<_>>();
+            new.id = manifest.generate_id(&new, &namespace_ids);
+        }
+        check_component(&new)?;
+        if !taken.insert((new.namespace.clone(), new.id.clone())) {
+            return Err(format!("{new} has the same id as another component"));
+        }
+        new_components.push(new);
+    }
+
+    for history in &histories {
+        let HistoryEntry::Component { e, after, .. } = history else {
+            continue;
+        };
+        match after {
+            Some(new) => {
+                commands
+                    .entity(*e)
+                    .insert((**new).clone())
+                    .trigger(RenderEv::default());
+            }
+            None => commands.entity(*e).despawn(),
+        }
+    }
+    for new in new_components {
+        let e = commands
+            .spawn(make_component(new.clone(), skin, skin_zoom))
+            .trigger(RenderEv::default())
+            .id();
+        histories.push(HistoryEntry::Component {
+            e,
+            before: None,
+            after: Some(new.into()),
+        });
+    }
+    let num_changed = histories.len();
+    if num_changed != 0 {
+        commands.trigger(HistoryEv::NewHistory(histories));
+    }
+    Ok(num_changed)
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && Path::new(name).file_name().is_some_and(|a| a == name)
+}
+
+#[expect(clippy::needless_pass_by_value)]
+pub fn on_script(
+    trigger: Trigger<ScriptEv>,
+    mut commands: Commands,
+    mut scripts: ResMut<Scripts>,
+    query: Query<(Entity, &PlaComponent, Has<SelectedComponent>)>,
+    namespaces: Res<Namespaces>,
+    manifest: Res<ProjectManifest>,
+    skin: Res<Skin>,
+    skin_zoom: Res<SkinZoom>,
+) {
+    match trigger.event() {
+        ScriptEv::Run => {
+            let components = query
+                .iter()
+                .map(|(e, pla, selected)| ScriptComponent {
+                    entity: Some(e),
+                    selected,
+                    pla: pla.to_mc_coords(),
+                })
+                .collect();
+            let output = Rc::new(RefCell::new(Vec::new()));
+            let result = run_script(&scripts.code, components, &output);
+            scripts
+                .output
+                .extend(output.take().into_iter().map(ScriptOutput::Print));
+            let result = result.map_err(|e| e.to_string()).and_then(|changes| {
+                apply_changes(
+                    changes,
+                    &mut commands,
+                    &query,
+                    &namespaces,
+                    &manifest,
+                    &skin,
+                    *skin_zoom,
+                )
+            });
+            scripts.output.push(match result {
+                Ok(num_changed) => ScriptOutput::Success(format!(
+                    "Script finished, {num_changed} components changed"
+                )),
+                Err(e) => {
+                    info!("Script failed: {e}");
+                    ScriptOutput::Error(e)
+                }
+            });
+        }
+        ScriptEv::Save => {
+            if !is_valid_name(&scripts.name) {
+                NOTIF_LOG.push(
+                    format!("Invalid script name {}", scripts.name),
+                    ToastLevel::Warning,
+                );
+                return;
+            }
+            let path = Scripts::path(&scripts.name);
+            match std::fs::write(&path, &scripts.code) {
+                Ok(()) => {
+                    info!(?path, "Saved script");
+                    NOTIF_LOG.push(
+                        format!("Saved script {}", scripts.name),
+                        ToastLevel::Success,
+                    );
+                }
+                Err(e) => {
+                    NOTIF_LOG.push(
+                        format!("Could not save script {}:\n{e}", path.display()),
+                        ToastLevel::Warning,
+                    );
+                }
+            }
+            scripts.refresh();
+        }
+        ScriptEv::Load(name) => match std::fs::read_to_string(Scripts::path(name)) {
+            Ok(code) => {
+                scripts.code = code;
+                name.clone_into(&mut scripts.name);
+            }
+            Err(e) => {
+                NOTIF_LOG.push(
+                    format!("Could not open script {name}:\n{e}"),
+                    ToastLevel::Warning,
+                );
+            }
+        },
+        ScriptEv::Delete(name) => {
+            if safe_delete(&Scripts::path(name), Some("script")).is_ok() {
+                NOTIF_LOG.push(format!("Deleted script {name}"), ToastLevel::Success);
+            }
+            scripts.refresh();
+        }
+    }
+}
+
+pub struct ScriptingPlugin;
+
+impl Plugin for ScriptingPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Scripts::load())
+            .add_observer(on_script)
+            .add_observer(console::on_script_console);
+    }
+}
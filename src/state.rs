@@ -18,6 +18,8 @@ pub enum EditorState {
     CreatingArea,
     EditingNodes,
     DeletingComponent,
+    Measuring,
+    Transforming,
 }
 
 impl EditorState {
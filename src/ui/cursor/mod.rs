@@ -91,7 +91,10 @@ pub fn cursor_icon_sy(
 
         ctx.ctx_for_entity_mut(e).set_cursor_icon(match **state {
             EditorState::Loading => egui::CursorIcon::Wait,
-            EditorState::Idle | EditorState::DeletingComponent | EditorState::EditingNodes => {
+            EditorState::Idle
+            | EditorState::DeletingComponent
+            | EditorState::EditingNodes
+            | EditorState::Transforming => {
                 if buttons.pressed(MouseButton::Left) {
                     egui::CursorIcon::Grabbing
                 } else if !hovered_comp.is_empty() {
@@ -103,6 +106,7 @@ pub fn cursor_icon_sy(
             EditorState::CreatingLine | EditorState::CreatingArea | EditorState::CreatingPoint => {
                 egui::CursorIcon::Cell
             }
+            EditorState::Measuring => egui::CursorIcon::Crosshair,
         });
     }
 }
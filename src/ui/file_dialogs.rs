@@ -14,6 +14,7 @@ pub struct FileDialogs {
     pub tile_settings_import: FileDialog,
     pub tile_settings_export: Option<(Basemap, FileDialog)>,
     pub project_select: FileDialog,
+    pub skin_export: Option<FileDialog>,
 }
 
 impl Default for FileDialogs {
@@ -22,6 +23,7 @@ impl Default for FileDialogs {
             tile_settings_import: TileSettingsEditor::import_dialog(),
             tile_settings_export: None,
             project_select: ProjectEditor::select_dialog(),
+            skin_export: None,
         }
     }
 }
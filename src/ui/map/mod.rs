@@ -46,6 +46,7 @@ impl Plugin for RenderingPlugin {
                 (tiles::show_tiles_sy, settings_editor::tile_settings_dialog)
                     .in_set(RenderingSet::Tiles),
             )
-            .add_observer(settings_editor::on_tile_settings);
+            .add_observer(settings_editor::on_tile_settings)
+            .add_observer(mouse_nav::on_jump_to);
     }
 }
@@ -169,3 +169,35 @@ pub fn mouse_zoom_sy(
     transform.translation.y = new_mouse_pos.y - d.y;
     Ok(())
 }
+
+/// Centres the camera on `pos` in Minecraft x/z coordinates, optionally also setting the zoom
+#[derive(Clone, Copy, Debug, PartialEq, Event)]
+pub struct JumpToEv {
+    pub pos: Vec2,
+    pub zoom: Option<f32>,
+}
+
+#[expect(clippy::needless_pass_by_value)]
+pub fn on_jump_to(
+    trigger: Trigger<JumpToEv>,
+    mut camera: Query<(&mut Transform, &mut Projection), With<Camera>>,
+    mut zoom: ResMut<Zoom>,
+    tile_settings: Res<TileSettings>,
+    misc_settings: Res<MiscSettings>,
+) -> Result {
+    let JumpToEv {
+        pos,
+        zoom: new_zoom,
+    } = *trigger.event();
+    let (mut transform, mut projection) = camera.single_mut()?;
+    transform.translation.x = pos.x;
+    transform.translation.y = -pos.y;
+    if let (Some(new_zoom), Projection::Orthographic(ort_proj)) = (new_zoom, &mut *projection) {
+        zoom.0 = new_zoom.clamp(
+            1.0,
+            f32::from(tile_settings.basemap().max_tile_zoom + misc_settings.additional_zoom),
+        );
+        ort_proj.scale = ((f32::from(tile_settings.basemap().max_tile_zoom) - 1.0) - zoom.0).exp2();
+    }
+    Ok(())
+}
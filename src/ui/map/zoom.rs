@@ -5,9 +5,26 @@ use crate::ui::map::settings::Basemap;
 #[derive(Copy, Clone, Debug, Resource)]
 pub struct Zoom(pub f32);
 
+/// The zoom level used by skins, where 0 is the most zoomed in
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Resource)]
+pub struct SkinZoom(pub u8);
+
+impl SkinZoom {
+    /// Number of blocks covered by one pixel of a skin width at this zoom level
+    #[must_use]
+    pub fn blocks_per_pixel(self) -> f32 {
+        f32::from(self.0).exp2()
+    }
+}
+
 impl Zoom {
     #[must_use]
     pub fn tile_size(self, basemap: &Basemap) -> f32 {
         (f32::from(basemap.max_tile_zoom) - self.0).exp2() * basemap.max_zoom_range
     }
+    #[must_use]
+    #[expect(clippy::cast_sign_loss)]
+    pub fn skin_zoom(self, basemap: &Basemap) -> SkinZoom {
+        SkinZoom((f32::from(basemap.max_tile_zoom) - self.0).round().max(0.0) as u8)
+    }
 }
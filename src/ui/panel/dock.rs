@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use bevy::{ecs::system::SystemParam, prelude::*};
 use bevy_egui::{egui, EguiContexts};
 use egui_dock::{DockArea, DockState, NodeIndex, Style, TabBodyStyle, TabStyle};
@@ -7,17 +9,29 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     component::{
-        actions::selecting::SelectedComponent,
-        panels::{component_editor::ComponentEditor, component_list::ComponentList},
-        pla2::PlaComponent,
+        actions::{selecting::SelectedComponent, transforming::TransformSettings},
+        panels::{
+            component_editor::ComponentEditor,
+            component_list::ComponentList,
+            skin_editor::{SkinDraft, SkinEditor},
+            transform_editor::TransformEditor,
+            type_migration::TypeMigration,
+        },
+        pla2::{ComponentType, PlaComponent},
+        presets::ComponentPresets,
         skin::Skin,
+        tools::creating::CreationDefaults,
     },
     dirs_paths::data_path,
     file::{load_msgpack, save_msgpack},
     history::{history_viewer::HistoryViewer, History},
     keymaps::{settings::KeymapSettings, settings_editor::KeymapSettingsEditor},
     misc_config::{settings::MiscSettings, settings_editor::MiscSettingsEditor},
-    project::{project_editor::ProjectEditor, Namespaces},
+    project::{
+        manifest::ProjectManifest, project_editor::ProjectEditor, settings::ProjectSettings,
+        Namespaces,
+    },
+    scripting::{console::ScriptConsole, Scripts},
     state::EditorState,
     ui::{
         cursor::mouse_pos::MousePosWorld,
@@ -58,6 +72,10 @@ pub enum DockWindows {
     NotifLogViewer,
     ComponentList,
     HistoryViewer,
+    TransformEditor,
+    SkinEditor,
+    TypeMigration,
+    ScriptConsole,
 }
 
 #[derive(Clone, Resource)]
@@ -130,6 +148,7 @@ pub struct PanelParams<'w, 's> {
     pub camera: Query<'w, 's, &'static mut Transform, With<Camera>>,
     pub commands: Commands<'w, 's>,
     pub skin: Res<'w, Skin>,
+    pub skin_draft: ResMut<'w, SkinDraft>,
     pub editor_state: Res<'w, State<EditorState>>,
     pub window_settings: ResMut<'w, WindowSettings>,
     pub tile_settings: ResMut<'w, TileSettings>,
@@ -139,10 +158,24 @@ pub struct PanelParams<'w, 's> {
     pub popups: ResMut<'w, Popups>,
     pub namespaces: ResMut<'w, Namespaces>,
     pub new_namespace: Local<'s, String>,
+    pub paste_coords: Local<'s, String>,
+    pub coord_input: Local<'s, String>,
+    pub type_search: Local<'s, String>,
+    pub preset_name: Local<'s, String>,
+    pub id_draft: Local<'s, Option<(Entity, String)>>,
+    pub rename_namespace: Local<'s, String>,
+    pub rename_type: Local<'s, Option<(usize, String)>>,
+    pub type_migrations: Local<'s, HashMap<(String, ComponentType), String>>,
     pub history: ResMut<'w, History>,
     pub mouse_pos_world: Res<'w, MousePosWorld>,
     pub pending_tiles: Res<'w, PendingTiles>,
     pub zoom: Res<'w, Zoom>,
+    pub transform_settings: ResMut<'w, TransformSettings>,
+    pub creation_defaults: ResMut<'w, CreationDefaults>,
+    pub presets: ResMut<'w, ComponentPresets>,
+    pub project_settings: ResMut<'w, ProjectSettings>,
+    pub manifest: ResMut<'w, ProjectManifest>,
+    pub scripts: ResMut<'w, Scripts>,
 }
 
 impl egui_dock::TabViewer for PanelParams<'_, '_> {
@@ -12,12 +12,20 @@ use crate::inspector::ShowInspector;
 use crate::{
     component::panels::{
         component_editor::OpenComponentEditorEv, component_list::OpenComponentListEv,
+        skin_editor::SkinEditorEv, transform_editor::OpenTransformEditorEv,
+        type_migration::OpenTypeMigrationEv,
     },
     history::{history_viewer::OpenHistoryViewerEv, HistoryEv},
     info_windows::InfoWindowsEv,
+    init::load_skin::SkinRefreshEv,
     keymaps::settings_editor::{KeymapSettingsEditor, OpenKeymapSettingsEv},
     misc_config::settings_editor::{MiscSettingsEditor, OpenMiscSettingsEv},
-    project::{events::ProjectEv, project_editor::OpenProjectEditorEv},
+    project::{
+        events::ProjectEv,
+        project_editor::OpenProjectEditorEv,
+        recent::{recent_projects_menu, RecentProjects},
+    },
+    scripting::console::OpenScriptConsoleEv,
     ui::{
         map::settings_editor::{TileSettingsEditor, TileSettingsEv},
         notif::{viewer::OpenNotifLogViewerEv, NotifLogRwLockExt, NOTIF_LOG},
@@ -38,6 +46,7 @@ pub fn ui_sy(
     mut commands: Commands,
     diagnostics: Res<DiagnosticsStore>,
     status: Res<Status>,
+    recent: Res<RecentProjects>,
     #[cfg(debug_assertions)] inspector: Option<Res<ShowInspector>>,
 ) {
     let Some(ctx) = ctx.try_ctx_mut() else {
@@ -64,11 +73,20 @@ pub fn ui_sy(
                     button!(ui, commands, "Manual", InfoWindowsEv::Manual);
                     button!(ui, commands, "Licenses", InfoWindowsEv::Licenses);
                     ui.separator();
+                    button!(ui, commands, "Skin", InfoWindowsEv::Skin);
+                    button!(
+                        ui,
+                        commands,
+                        "Refresh Skin",
+                        SkinRefreshEv::Check { manual: true }
+                    );
+                    ui.separator();
                     button!(ui, commands, "Quit", InfoWindowsEv::Quit(false));
                 },
             );
             egui::menu::menu_button(ui, "File", |ui| {
                 button!(ui, commands, "Open...", ProjectEv::Open);
+                recent_projects_menu(ui, &mut commands, &recent);
                 button!(ui, commands, "Reload", ProjectEv::Reload);
                 button!(ui, commands, "Save", ProjectEv::Save(false));
             });
@@ -80,6 +98,10 @@ pub fn ui_sy(
             egui::menu::menu_button(ui, "View", |ui| {
                 button!(ui, commands, "Component List", OpenComponentListEv);
                 button!(ui, commands, "Component Editor", OpenComponentEditorEv);
+                button!(ui, commands, "Transform", OpenTransformEditorEv);
+                button!(ui, commands, "Skin Editor", SkinEditorEv::Open);
+                button!(ui, commands, "Type Migration", OpenTypeMigrationEv);
+                button!(ui, commands, "Scripting", OpenScriptConsoleEv);
                 button!(ui, commands, "Project", OpenProjectEditorEv);
                 button!(ui, commands, "History", OpenHistoryViewerEv);
                 button!(ui, commands, "Notification Log", OpenNotifLogViewerEv);
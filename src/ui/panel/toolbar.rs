@@ -1,6 +1,14 @@
+use bevy::prelude::*;
 use bevy_egui::egui;
+use itertools::Itertools;
 
 use crate::{
+    component::{
+        pla2::ComponentType,
+        presets::ComponentPresets,
+        skin::{Skin, SkinComponent},
+        tools::creating::{CoordInputEv, CreationDefaults},
+    },
     state::{ChangeStateEv, EditorState},
     ui::{map::mouse_nav::ScrollMode, panel::dock::PanelParams},
 };
@@ -13,6 +21,11 @@ pub fn toolbar(ui: &mut egui::Ui, params: &mut PanelParams) -> egui::InnerRespon
         pending_tiles,
         zoom,
         misc_settings,
+        coord_input,
+        skin,
+        creation_defaults,
+        type_search,
+        presets,
         ..
     } = params;
     let mut new_state = ***editor_state;
@@ -29,6 +42,8 @@ pub fn toolbar(ui: &mut egui::Ui, params: &mut PanelParams) -> egui::InnerRespon
             ui.separator();
             button!("Edit Nodes", EditorState::EditingNodes);
             button!("Delete", EditorState::DeletingComponent);
+            button!("Measure", EditorState::Measuring);
+            button!("Transform", EditorState::Transforming);
 
             ui.separator();
             ui.label("Create...");
@@ -48,21 +63,230 @@ pub fn toolbar(ui: &mut egui::Ui, params: &mut PanelParams) -> egui::InnerRespon
                 ui.separator();
             });
         });
+        if let Some(ty) = editor_state.component_type() {
+            ui.horizontal(|ui| {
+                // Text and number edits only flag a change once finished, so they are saved once
+                let (mut defaults_edited, mut preset_edited) = (false, false);
+                preset_picker(ui, ty, creation_defaults, presets);
+                if let Some(preset) = creation_defaults
+                    .preset
+                    .as_ref()
+                    .and_then(|a| presets.bypass_change_detection().0.get_mut(a))
+                    .filter(|a| a.shape == ty)
+                {
+                    preset_edited |= ui
+                        .add(
+                            egui::TextEdit::singleline(&mut preset.id)
+                                .hint_text("id, random if empty")
+                                .desired_width(100.0),
+                        )
+                        .lost_focus();
+                    preset_edited |= ui
+                        .add(
+                            egui::TextEdit::singleline(&mut preset.display_name)
+                                .hint_text("Displayed as")
+                                .desired_width(100.0),
+                        )
+                        .lost_focus();
+                    let resp = ui.add(egui::DragValue::new(&mut preset.counter).prefix("{n} = "));
+                    preset_edited |= resp.lost_focus() || resp.drag_stopped();
+                } else {
+                    type_picker(ui, ty, skin, creation_defaults, type_search);
+
+                    let defaults = creation_defaults.bypass_change_detection();
+                    let mut tags = defaults.tags.join(",");
+                    let resp = ui.add(
+                        egui::TextEdit::singleline(&mut tags)
+                            .hint_text("Default tags")
+                            .desired_width(100.0),
+                    );
+                    if resp.changed() {
+                        defaults.tags = tags.split(',').map(|t| t.trim().to_owned()).collect();
+                    }
+                    if resp.lost_focus() {
+                        defaults.tags.retain(|t| !t.is_empty());
+                        defaults_edited = true;
+                    }
+                    let resp = ui.add(
+                        egui::DragValue::new(&mut defaults.layer)
+                            .speed(0.1)
+                            .range(-10.0..=10.0)
+                            .prefix("Layer: "),
+                    );
+                    defaults_edited |= resp.lost_focus() || resp.drag_stopped();
+                }
+                if defaults_edited {
+                    creation_defaults.set_changed();
+                }
+                if preset_edited {
+                    presets.set_changed();
+                }
+                ui.separator();
+
+                let resp = ui.add(
+                    egui::TextEdit::singleline(&mut **coord_input)
+                        .hint_text("x z / @dx dz / <bearing distance")
+                        .desired_width(200.0),
+                );
+                if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    if let Ok(ev) = CoordInputEv::parse(&**coord_input) {
+                        commands.trigger(ev);
+                        coord_input.clear();
+                    }
+                    resp.request_focus();
+                }
+                if !coord_input.is_empty() {
+                    if let Err(e) = CoordInputEv::parse(&**coord_input) {
+                        ui.colored_label(egui::Color32::RED, e.to_string());
+                    }
+                }
+            });
+        }
     });
     if new_state != ***editor_state {
         commands.trigger(ChangeStateEv(new_state));
         params.status.set(match new_state {
             EditorState::Idle => match misc_settings.scroll_mode {
-                ScrollMode::Zoom => "Idle: L-Click to select component. L-Click-drag to pan. Scroll to zoom.",
-                ScrollMode::Pan => "Idle: L-Click to select component. Scroll or L-Click-drag to pan. Shift and scroll to pan horizontally. Ctrl and scroll to zoom.",
+                ScrollMode::Zoom => "Idle: L-Click to select component. L-Click-drag to pan. Scroll to zoom. Arrow keys to nudge selected component.",
+                ScrollMode::Pan => "Idle: L-Click to select component. Scroll or L-Click-drag to pan. Shift and scroll to pan horizontally. Ctrl and scroll to zoom. Arrow keys to nudge selected component.",
             },
             EditorState::EditingNodes => "Editing nodes: R-click and drag circles to create node. R-click large circle without dragging to delete node.",
-            EditorState::CreatingPoint => "Creating points: L-click to create point.",
-            EditorState::CreatingLine => "Creating lines: L-click to start and continue line, L-click previous node to undo it. R-click to end. Alt to snap to angle.",
-            EditorState::CreatingArea => "Creating areas: L-click to start and continue line, L-click previous node to undo it. L-click first node or R-click to end. Alt to snap to angle.",
+            EditorState::CreatingPoint => "Creating points: L-click or type coordinates to create point.",
+            EditorState::CreatingLine => "Creating lines: L-click or type coordinates to start and continue line, L-click previous node to undo it. R-click to end. Alt to snap to angle.",
+            EditorState::CreatingArea => "Creating areas: L-click or type coordinates to start and continue line, L-click previous node to undo it. L-click first node or R-click to end. Alt to snap to angle.",
             EditorState::DeletingComponent => "Deleting components: L-click to delete node.",
+            EditorState::Measuring => "Measuring: L-click to add a node. R-click to clear.",
+            EditorState::Transforming => "Transforming: Shift-L-click to select multiple components. R-click and drag the top circle to rotate, or the corner circles to scale. M-click to set the pivot.",
             _ => ""
         });
     }
     resp
 }
+
+fn type_picker(
+    ui: &mut egui::Ui,
+    ty: ComponentType,
+    skin: &Skin,
+    creation_defaults: &mut ResMut<CreationDefaults>,
+    search: &mut String,
+) {
+    let chosen = creation_defaults.type_name(ty, skin);
+    let mut new_choice = None;
+    let mut toggle_favourite = None;
+    let mut row = |ui: &mut egui::Ui, skin_type: &SkinComponent, favourite: bool| {
+        ui.horizontal(|ui| {
+            if ui
+                .small_button(if favourite { "★" } else { "☆" })
+                .on_hover_text("Favourite")
+                .clicked()
+            {
+                toggle_favourite = Some(skin_type.name().to_owned());
+            }
+            let label = skin_type.widget_text(ui, &egui::TextStyle::Button);
+            if ui
+                .selectable_label(*skin_type.name() == chosen, label)
+                .clicked()
+            {
+                new_choice = Some(skin_type.name().to_owned());
+            }
+        });
+    };
+
+    egui::ComboBox::from_id_salt("type_picker")
+        .selected_text(skin.show_type(&chosen, ui, &egui::TextStyle::Button))
+        .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+        .height(400.0)
+        .show_ui(ui, |ui| {
+            ui.add(egui::TextEdit::singleline(search).hint_text("Search"));
+            let search = search.to_lowercase();
+            let matches = |a: &&SkinComponent| {
+                a.get_type() == ty && a.name().to_lowercase().contains(&search)
+            };
+            let is_favourite =
+                |a: &SkinComponent| creation_defaults.favourites.iter().any(|b| b == a.name());
+
+            let favourites = creation_defaults
+                .favourites
+                .iter()
+                .filter_map(|a| skin.get_type(a))
+                .filter(matches)
+                .collect::<Vec<_>>();
+            if !favourites.is_empty() {
+                ui.label("Favourites");
+                for skin_type in favourites {
+                    row(ui, skin_type, true);
+                }
+                ui.separator();
+            }
+            let recents = creation_defaults
+                .recents
+                .iter()
+                .filter_map(|a| skin.get_type(a))
+                .filter(matches)
+                .collect::<Vec<_>>();
+            if !recents.is_empty() {
+                ui.label("Recent");
+                for skin_type in recents {
+                    row(ui, skin_type, is_favourite(skin_type));
+                }
+                ui.separator();
+            }
+            for skin_type in skin
+                .types
+                .iter()
+                .filter(matches)
+                .sorted_by(|a, b| a.name().cmp(b.name()))
+            {
+                row(ui, skin_type, is_favourite(skin_type));
+            }
+        });
+
+    if let Some(name) = toggle_favourite {
+        creation_defaults.toggle_favourite(&name);
+    }
+    if let Some(name) = new_choice {
+        creation_defaults.choose(ty, name);
+    }
+}
+
+fn preset_picker(
+    ui: &mut egui::Ui,
+    ty: ComponentType,
+    creation_defaults: &mut ResMut<CreationDefaults>,
+    presets: &mut ResMut<ComponentPresets>,
+) {
+    let mut chosen = creation_defaults.preset.clone();
+    let mut delete = None;
+    egui::ComboBox::from_id_salt("preset_picker")
+        .selected_text(
+            creation_defaults
+                .preset
+                .as_deref()
+                .filter(|a| presets.0.get(*a).is_some_and(|a| a.shape == ty))
+                .unwrap_or("No preset"),
+        )
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut chosen, None, "No preset");
+            for (name, _) in presets.0.iter().filter(|(_, a)| a.shape == ty) {
+                ui.horizontal(|ui| {
+                    if ui
+                        .small_button("❌")
+                        .on_hover_text("Delete preset")
+                        .clicked()
+                    {
+                        delete = Some(name.to_owned());
+                    }
+                    ui.selectable_value(&mut chosen, Some(name.to_owned()), name.as_str());
+                });
+            }
+        });
+    if let Some(delete) = delete {
+        presets.0.remove(&delete);
+    }
+    if chosen.as_ref().is_some_and(|a| !presets.0.contains_key(a)) {
+        chosen = None;
+    }
+    if chosen != creation_defaults.preset {
+        creation_defaults.preset = chosen;
+    }
+}